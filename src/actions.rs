@@ -0,0 +1,271 @@
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::launcher;
+
+pub const ACTION_PREFIX: &str = "gomi.action.";
+const CLIPBOARD_ID: &str = "gomi.action.clipboard";
+const READING_LIST_ID: &str = "gomi.action.reading-list";
+const SCRIPT_ID: &str = "gomi.action.script";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    CopyToClipboard,
+    AppendToReadingList(PathBuf),
+    PipeToScript(String),
+}
+
+impl Action {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::CopyToClipboard => CLIPBOARD_ID,
+            Action::AppendToReadingList(_) => READING_LIST_ID,
+            Action::PipeToScript(_) => SCRIPT_ID,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        display_name(self.id()).unwrap()
+    }
+
+    pub fn perform(&self, url: &str) -> io::Result<()> {
+        match self {
+            Action::CopyToClipboard => {
+                wait_in_background(pipe_to_command(&["pbcopy".to_string()], url)?);
+                Ok(())
+            }
+            Action::AppendToReadingList(path) => {
+                append_to_reading_list(path, url, SystemTime::now())
+            }
+            Action::PipeToScript(command) => {
+                wait_in_background(run_script(command, url)?);
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn is_action(id: &str) -> bool {
+    id.starts_with(ACTION_PREFIX)
+}
+
+pub fn display_name(id: &str) -> Option<&'static str> {
+    match id {
+        CLIPBOARD_ID => Some("Copy Link"),
+        READING_LIST_ID => Some("Add to Reading List"),
+        SCRIPT_ID => Some("Run Script"),
+        _ => None,
+    }
+}
+
+pub fn available(reading_list_path: Option<PathBuf>, script_command: &str) -> Vec<Action> {
+    let mut actions = vec![Action::CopyToClipboard];
+    if let Some(path) = reading_list_path {
+        actions.push(Action::AppendToReadingList(path));
+    }
+    if !script_command.trim().is_empty() {
+        actions.push(Action::PipeToScript(script_command.trim().to_string()));
+    }
+    actions
+}
+
+pub fn find(id: &str, actions: &[Action]) -> Option<Action> {
+    actions.iter().find(|action| action.id() == id).cloned()
+}
+
+pub fn append_to_reading_list(path: &Path, url: &str, time: SystemTime) -> io::Result<()> {
+    let timestamp = format_timestamp(time);
+    if path.extension().and_then(|extension| extension.to_str()) == Some("json") {
+        let mut entries: Vec<Value> = match fs::read_to_string(path) {
+            Ok(content) if !content.trim().is_empty() => serde_json::from_str(&content)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Ok(_) => Vec::new(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        entries.push(json!({ "url": url, "added_at": timestamp }));
+        fs::write(path, serde_json::to_string_pretty(&entries).unwrap() + "\n")
+    } else {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "- {} — {}", markdown_link(url), &timestamp[..10])
+    }
+}
+
+pub fn run_script(command: &str, url: &str) -> io::Result<Child> {
+    let args: Vec<String> = launcher::split_command(command)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid script command"))?
+        .into_iter()
+        .map(|arg| arg.replace("{url}", url))
+        .collect();
+    pipe_to_command(&args, url)
+}
+
+fn markdown_link(url: &str) -> String {
+    let label = url
+        .replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]");
+    let target = url
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29");
+    format!("[{}]({})", label, target)
+}
+
+fn pipe_to_command(args: &[String], input: &str) -> io::Result<Child> {
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Err(err) = writeln!(stdin, "{}", input) {
+            if err.kind() != io::ErrorKind::BrokenPipe {
+                wait_in_background(child);
+                return Err(err);
+            }
+        }
+    }
+    Ok(child)
+}
+
+fn wait_in_background(mut child: Child) {
+    std::thread::spawn(move || child.wait());
+}
+
+fn format_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let (days, seconds) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    // civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn time() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_709_251_199)
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(time()), "2024-02-29T23:59:59Z");
+    }
+
+    #[test]
+    fn test_available_actions() {
+        assert_eq!(available(None, "  "), vec![Action::CopyToClipboard]);
+        let actions = available(Some(PathBuf::from("/tmp/reading.md")), " yt-dlp {url} ");
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            find("gomi.action.script", &actions),
+            Some(Action::PipeToScript("yt-dlp {url}".to_string()))
+        );
+        assert_eq!(find("com.google.Chrome", &actions), None);
+        assert!(is_action(actions[1].id()));
+        assert_eq!(actions[1].name(), "Add to Reading List");
+        assert_eq!(display_name("com.google.Chrome"), None);
+    }
+
+    #[test]
+    fn test_append_to_markdown_reading_list() {
+        let dir = test_dir("gomi-reading-list-md");
+        let path = dir.join("reading.md");
+        append_to_reading_list(&path, "https://example.com/a", time()).unwrap();
+        append_to_reading_list(&path, "https://example.com/b", UNIX_EPOCH).unwrap();
+        append_to_reading_list(
+            &path,
+            "https://en.wikipedia.org/wiki/Rust_(a b)[1]",
+            UNIX_EPOCH,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "- [https://example.com/a](https://example.com/a) — 2024-02-29\n\
+             - [https://example.com/b](https://example.com/b) — 1970-01-01\n\
+             - [https://en.wikipedia.org/wiki/Rust_(a b)\\[1\\]](https://en.wikipedia.org/wiki/Rust_%28a%20b%29[1]) — 1970-01-01\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_append_to_json_reading_list() {
+        let dir = test_dir("gomi-reading-list-json");
+        let path = dir.join("reading.json");
+        append_to_reading_list(&path, "https://example.com/a", time()).unwrap();
+        append_to_reading_list(&path, "https://example.com/b", UNIX_EPOCH).unwrap();
+        let entries: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            entries,
+            json!([
+                { "url": "https://example.com/a", "added_at": "2024-02-29T23:59:59Z" },
+                { "url": "https://example.com/b", "added_at": "1970-01-01T00:00:00Z" }
+            ])
+        );
+
+        let broken = dir.join("broken.json");
+        fs::write(&broken, "{ not json").unwrap();
+        assert!(append_to_reading_list(&broken, "https://example.com", time()).is_err());
+        assert_eq!(fs::read_to_string(&broken).unwrap(), "{ not json");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_script() {
+        let dir = test_dir("gomi-run-script");
+        let output = dir.join("out.txt");
+        let command = format!(
+            "sh -c 'cat > \"$0\"; echo \"$1\" >> \"$0\"' '{}' {{url}}",
+            output.display()
+        );
+        let status = run_script(&command, "https://example.com/watch?v=1&t=2")
+            .unwrap()
+            .wait()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "https://example.com/watch?v=1&t=2\nhttps://example.com/watch?v=1&t=2\n"
+        );
+        assert!(run_script("'unterminated", "https://example.com").is_err());
+        let status = run_script("sh -c 'exec 0<&-'", &"x".repeat(1 << 20))
+            .unwrap()
+            .wait()
+            .unwrap();
+        assert!(status.success());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
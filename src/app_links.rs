@@ -0,0 +1,359 @@
+use url::Url;
+
+pub struct AppLink {
+    pub name: &'static str,
+    pub bundle_id: &'static str,
+    pub bundle_name: &'static str,
+    to_app: fn(&Url) -> Option<String>,
+    to_web: fn(&Url) -> Option<String>,
+}
+
+pub const APP_LINKS: &[AppLink] = &[
+    AppLink {
+        name: "Zoom",
+        bundle_id: "us.zoom.xos",
+        bundle_name: "zoom.us.app",
+        to_app: zoom_to_app,
+        to_web: zoom_to_web,
+    },
+    AppLink {
+        name: "Slack",
+        bundle_id: "com.tinyspeck.slackmacgap",
+        bundle_name: "Slack.app",
+        to_app: slack_to_app,
+        to_web: slack_to_web,
+    },
+    AppLink {
+        name: "Figma",
+        bundle_id: "com.figma.Desktop",
+        bundle_name: "Figma.app",
+        to_app: figma_to_app,
+        to_web: figma_to_web,
+    },
+    AppLink {
+        name: "Spotify",
+        bundle_id: "com.spotify.client",
+        bundle_name: "Spotify.app",
+        to_app: spotify_to_app,
+        to_web: spotify_to_web,
+    },
+    AppLink {
+        name: "Notion",
+        bundle_id: "notion.id",
+        bundle_name: "Notion.app",
+        to_app: notion_to_app,
+        to_web: notion_to_web,
+    },
+    AppLink {
+        name: "Visual Studio Code",
+        bundle_id: "com.microsoft.VSCode",
+        bundle_name: "Visual Studio Code.app",
+        to_app: vscode_to_app,
+        to_web: vscode_to_web,
+    },
+];
+
+impl AppLink {
+    pub fn path(&self) -> String {
+        format!("/Applications/{}", self.bundle_name)
+    }
+
+    pub fn translate(&self, url: &str) -> Option<String> {
+        Url::parse(url).ok().and_then(|url| (self.to_app)(&url))
+    }
+
+    pub fn to_web(&self, app_url: &str) -> Option<String> {
+        Url::parse(app_url).ok().and_then(|url| (self.to_web)(&url))
+    }
+}
+
+pub fn find_by_bundle_id(bundle_id: &str) -> Option<&'static AppLink> {
+    APP_LINKS
+        .iter()
+        .find(|app_link| app_link.bundle_id == bundle_id)
+}
+
+pub fn can_open(bundle_id: &str, url: &str) -> bool {
+    match find_by_bundle_id(bundle_id) {
+        Some(app_link) => app_link.translate(url).is_some(),
+        None => true,
+    }
+}
+
+fn with_query(base: String, url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", base, query),
+        None => base,
+    }
+}
+
+fn query_value(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.into_owned())
+}
+
+fn zoom_to_app(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    if host != "zoom.us" && !host.ends_with(".zoom.us") {
+        return None;
+    }
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    let confno = match segments.as_slice() {
+        ["j", confno] if !confno.is_empty() => *confno,
+        _ => return None,
+    };
+    let mut app_url = Url::parse(&format!("zoommtg://{}/join", host)).ok()?;
+    app_url.query_pairs_mut().append_pair("confno", confno);
+    if let Some(pwd) = query_value(url, "pwd") {
+        app_url.query_pairs_mut().append_pair("pwd", &pwd);
+    }
+    Some(app_url.to_string())
+}
+
+fn zoom_to_web(url: &Url) -> Option<String> {
+    if url.scheme() != "zoommtg" || url.path() != "/join" {
+        return None;
+    }
+    let host = url.host_str()?;
+    let confno = query_value(url, "confno")?;
+    let mut web_url = Url::parse(&format!("https://{}/j/{}", host, confno)).ok()?;
+    if let Some(pwd) = query_value(url, "pwd") {
+        web_url.query_pairs_mut().append_pair("pwd", &pwd);
+    }
+    Some(web_url.to_string())
+}
+
+fn slack_to_app(url: &Url) -> Option<String> {
+    if url.host_str()? != "app.slack.com" {
+        return None;
+    }
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    match segments.as_slice() {
+        ["client", team, channel] if !team.is_empty() && !channel.is_empty() => {
+            Some(format!("slack://channel?team={}&id={}", team, channel))
+        }
+        _ => None,
+    }
+}
+
+fn slack_to_web(url: &Url) -> Option<String> {
+    if url.scheme() != "slack" || url.host_str()? != "channel" {
+        return None;
+    }
+    let team = query_value(url, "team")?;
+    let channel = query_value(url, "id")?;
+    Some(format!("https://app.slack.com/client/{}/{}", team, channel))
+}
+
+fn figma_to_app(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    if host != "www.figma.com" && host != "figma.com" {
+        return None;
+    }
+    let kind = url.path_segments()?.next()?;
+    if !["file", "design", "proto", "board"].contains(&kind) {
+        return None;
+    }
+    Some(with_query(format!("figma:/{}", url.path()), url))
+}
+
+fn figma_to_web(url: &Url) -> Option<String> {
+    if url.scheme() != "figma" {
+        return None;
+    }
+    let kind = url.host_str()?;
+    Some(with_query(
+        format!("https://www.figma.com/{}{}", kind, url.path()),
+        url,
+    ))
+}
+
+const SPOTIFY_KINDS: [&str; 6] = ["track", "album", "artist", "playlist", "episode", "show"];
+
+fn spotify_to_app(url: &Url) -> Option<String> {
+    if url.host_str()? != "open.spotify.com" {
+        return None;
+    }
+    let segments: Vec<&str> = url
+        .path_segments()?
+        .filter(|segment| !segment.starts_with("intl-"))
+        .collect();
+    match segments.as_slice() {
+        [kind, id] if SPOTIFY_KINDS.contains(kind) && !id.is_empty() => {
+            Some(format!("spotify:{}:{}", kind, id))
+        }
+        _ => None,
+    }
+}
+
+fn spotify_to_web(url: &Url) -> Option<String> {
+    if url.scheme() != "spotify" {
+        return None;
+    }
+    let (kind, id) = url.path().split_once(':')?;
+    if !SPOTIFY_KINDS.contains(&kind) || id.is_empty() {
+        return None;
+    }
+    Some(format!("https://open.spotify.com/{}/{}", kind, id))
+}
+
+fn notion_to_app(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    if host != "www.notion.so" && host != "notion.so" {
+        return None;
+    }
+    Some(with_query(format!("notion://{}{}", host, url.path()), url))
+}
+
+fn notion_to_web(url: &Url) -> Option<String> {
+    if url.scheme() != "notion" {
+        return None;
+    }
+    let host = url.host_str()?;
+    Some(with_query(format!("https://{}{}", host, url.path()), url))
+}
+
+fn vscode_to_app(url: &Url) -> Option<String> {
+    if url.host_str()? != "marketplace.visualstudio.com" || url.path() != "/items" {
+        return None;
+    }
+    let item_name = query_value(url, "itemName")?;
+    Some(format!("vscode:extension/{}", item_name))
+}
+
+fn vscode_to_web(url: &Url) -> Option<String> {
+    if url.scheme() != "vscode" {
+        return None;
+    }
+    let item_name = url.path().strip_prefix("extension/")?;
+    Some(format!(
+        "https://marketplace.visualstudio.com/items?itemName={}",
+        item_name
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_link(name: &str) -> &'static AppLink {
+        APP_LINKS
+            .iter()
+            .find(|app_link| app_link.name == name)
+            .unwrap()
+    }
+
+    fn assert_round_trip(name: &str, web_url: &str, app_url: &str) {
+        let app_link = app_link(name);
+        assert_eq!(app_link.translate(web_url), Some(app_url.to_string()));
+        assert_eq!(app_link.to_web(app_url), Some(web_url.to_string()));
+    }
+
+    #[test]
+    fn test_zoom_round_trip() {
+        assert_round_trip(
+            "Zoom",
+            "https://zoom.us/j/123?pwd=x",
+            "zoommtg://zoom.us/join?confno=123&pwd=x",
+        );
+        assert_round_trip(
+            "Zoom",
+            "https://acme.zoom.us/j/987654",
+            "zoommtg://acme.zoom.us/join?confno=987654",
+        );
+        assert!(app_link("Zoom")
+            .translate("https://zoom.us/signin")
+            .is_none());
+    }
+
+    #[test]
+    fn test_slack_round_trip() {
+        assert_round_trip(
+            "Slack",
+            "https://app.slack.com/client/T0123/C0456",
+            "slack://channel?team=T0123&id=C0456",
+        );
+        assert!(app_link("Slack")
+            .translate("https://acme.slack.com/archives/C0456")
+            .is_none());
+    }
+
+    #[test]
+    fn test_figma_round_trip() {
+        assert_round_trip(
+            "Figma",
+            "https://www.figma.com/file/AbC123/Design-System?node-id=1-2",
+            "figma://file/AbC123/Design-System?node-id=1-2",
+        );
+        assert_round_trip(
+            "Figma",
+            "https://www.figma.com/design/AbC123/Landing",
+            "figma://design/AbC123/Landing",
+        );
+        assert!(app_link("Figma")
+            .translate("https://www.figma.com/pricing")
+            .is_none());
+    }
+
+    #[test]
+    fn test_spotify_round_trip() {
+        assert_round_trip(
+            "Spotify",
+            "https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC",
+            "spotify:track:4uLU6hMCjMI75M1A2tKUQC",
+        );
+        assert_round_trip(
+            "Spotify",
+            "https://open.spotify.com/playlist/37i9dQZF1DXcBWIGoYBM5M",
+            "spotify:playlist:37i9dQZF1DXcBWIGoYBM5M",
+        );
+        assert_eq!(
+            app_link("Spotify").translate("https://open.spotify.com/intl-de/album/1A2B"),
+            Some("spotify:album:1A2B".to_string())
+        );
+        assert!(app_link("Spotify")
+            .translate("https://open.spotify.com/search/foo")
+            .is_none());
+    }
+
+    #[test]
+    fn test_notion_round_trip() {
+        assert_round_trip(
+            "Notion",
+            "https://www.notion.so/acme/Roadmap-0123456789abcdef",
+            "notion://www.notion.so/acme/Roadmap-0123456789abcdef",
+        );
+        assert_round_trip(
+            "Notion",
+            "https://www.notion.so/acme/Roadmap-0123?pvs=4",
+            "notion://www.notion.so/acme/Roadmap-0123?pvs=4",
+        );
+        assert!(app_link("Notion")
+            .translate("https://example.com/notion")
+            .is_none());
+    }
+
+    #[test]
+    fn test_vscode_round_trip() {
+        assert_round_trip(
+            "Visual Studio Code",
+            "https://marketplace.visualstudio.com/items?itemName=rust-lang.rust-analyzer",
+            "vscode:extension/rust-lang.rust-analyzer",
+        );
+        assert!(app_link("Visual Studio Code")
+            .translate("https://marketplace.visualstudio.com/search")
+            .is_none());
+    }
+
+    #[test]
+    fn test_find_by_bundle_id() {
+        let app_link = find_by_bundle_id("us.zoom.xos").unwrap();
+        assert_eq!(app_link.name, "Zoom");
+        assert_eq!(app_link.path(), "/Applications/zoom.us.app");
+        assert!(find_by_bundle_id("com.apple.Safari").is_none());
+        assert!(can_open("com.apple.Safari", "https://zoom.us/signin"));
+        assert!(can_open("us.zoom.xos", "https://zoom.us/j/1"));
+        assert!(!can_open("us.zoom.xos", "https://zoom.us/signin"));
+    }
+}
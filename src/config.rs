@@ -1,7 +1,7 @@
 use std::env;
 
 pub fn get_data_dir() -> String {
-    format!("{}", env::var("HOME").unwrap())
+    env::var("HOME").unwrap()
 }
 
 pub fn get_db_path() -> String {
@@ -12,5 +12,7 @@ pub const MENU_WINDOW_WIDTH: f32 = 300.0;
 pub const MENU_WINDOW_HEIGHT: f32 = 300.0;
 pub const SETTING_WINDOW_WIDTH: f32 = 1024.0;
 pub const SETTING_WINDOW_HEIGHT: f32 = 768.0;
+pub const HUD_WINDOW_WIDTH: f32 = 360.0;
+pub const HUD_WINDOW_HEIGHT: f32 = 44.0;
 pub const LOG_DIR: &str = "/tmp";
 pub const LOG_FILE: &str = "gomi.log";
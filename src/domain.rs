@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::OnceLock;
+
+const PUBLIC_SUFFIX_LIST: &str = include_str!("../resources/public_suffix_list.dat");
+
+struct SuffixRules {
+    exact: HashSet<String>,
+    wildcard: HashSet<String>,
+    exception: HashSet<String>,
+}
+
+fn rules() -> &'static SuffixRules {
+    static RULES: OnceLock<SuffixRules> = OnceLock::new();
+    RULES.get_or_init(|| parse_rules(PUBLIC_SUFFIX_LIST))
+}
+
+fn parse_rules(list: &str) -> SuffixRules {
+    let mut rules = SuffixRules {
+        exact: HashSet::new(),
+        wildcard: HashSet::new(),
+        exception: HashSet::new(),
+    };
+    for line in list.lines() {
+        let Some(rule) = line.split_whitespace().next() else {
+            continue;
+        };
+        if rule.starts_with("//") {
+            continue;
+        }
+        if let Some(rule) = rule.strip_prefix('!') {
+            rules.exception.insert(to_ascii(rule));
+        } else if let Some(rule) = rule.strip_prefix("*.") {
+            rules.wildcard.insert(to_ascii(rule));
+        } else {
+            rules.exact.insert(to_ascii(rule));
+        }
+    }
+    rules
+}
+
+fn to_ascii(domain: &str) -> String {
+    if domain.is_ascii() {
+        return domain.to_lowercase();
+    }
+    match url::Host::parse(domain) {
+        Ok(url::Host::Domain(domain)) => domain,
+        _ => domain.to_lowercase(),
+    }
+}
+
+fn public_suffix_len(rules: &SuffixRules, labels: &[&str]) -> usize {
+    for index in 0..labels.len() {
+        let suffix = labels[index..].join(".");
+        if rules.exception.contains(&suffix) {
+            return labels.len() - index - 1;
+        }
+        if rules.exact.contains(&suffix)
+            || (index + 1 < labels.len() && rules.wildcard.contains(&labels[index + 1..].join(".")))
+        {
+            return labels.len() - index;
+        }
+    }
+    1
+}
+
+pub fn registrable_domain(host: &str) -> Option<String> {
+    let host = host.trim_end_matches('.').to_lowercase();
+    if host.is_empty() || host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+        return None;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let suffix_len = public_suffix_len(rules(), &labels);
+    (labels.len() > suffix_len).then(|| labels[labels.len() - suffix_len - 1..].join("."))
+}
+
+pub fn host_matches_domain(host: &str, domain: &str) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+pub(crate) fn url_host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registrable_domain() {
+        let cases = [
+            ("github.com", Some("github.com")),
+            ("gist.github.com", Some("github.com")),
+            ("WWW.Example.CO.UK.", Some("example.co.uk")),
+            ("foo.bar.github.io", Some("bar.github.io")),
+            ("b.a.kobe.jp", Some("b.a.kobe.jp")),
+            ("www.ck", Some("www.ck")),
+            ("a.b.ck", Some("a.b.ck")),
+            (
+                "xn--85x722f.xn--55qx5d.cn",
+                Some("xn--85x722f.xn--55qx5d.cn"),
+            ),
+            ("intranet.corp", Some("intranet.corp")),
+            ("co.uk", None),
+            ("com", None),
+            ("localhost", None),
+            ("127.0.0.1", None),
+            ("[::1]", None),
+            ("", None),
+        ];
+        for (host, expected) in cases {
+            assert_eq!(
+                registrable_domain(host).as_deref(),
+                expected,
+                "registrable domain of {:?}",
+                host
+            );
+        }
+    }
+
+    #[test]
+    fn test_host_matches_domain() {
+        assert!(host_matches_domain("github.com", "github.com"));
+        assert!(host_matches_domain("Gist.GitHub.com", "github.com"));
+        assert!(!host_matches_domain("notgithub.com", "github.com"));
+        assert!(!host_matches_domain("github.com.evil.net", "github.com"));
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://Docs.GitHub.com:8443/a?b=c").as_deref(),
+            Some("docs.github.com")
+        );
+        assert_eq!(url_host("mailto:someone@example.com"), None);
+        assert_eq!(url_host("not a url"), None);
+    }
+}
@@ -0,0 +1,85 @@
+const MATCH_SCORE: i32 = 1;
+const CONSECUTIVE_BONUS: i32 = 5;
+const WORD_START_BONUS: i32 = 8;
+const MAX_LEADING_PENALTY: i32 = 5;
+
+pub fn score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let candidate: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+
+    let mut score = 0;
+    let mut query_index = 0;
+    let mut last_match: Option<usize> = None;
+    for (index, c) in candidate.iter().enumerate() {
+        if query_index == query.len() {
+            break;
+        }
+        if *c != query[query_index] {
+            continue;
+        }
+        score += MATCH_SCORE;
+        match last_match {
+            Some(last) if last + 1 == index => score += CONSECUTIVE_BONUS,
+            None => score -= (index as i32).min(MAX_LEADING_PENALTY),
+            _ => (),
+        }
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += WORD_START_BONUS;
+        }
+        last_match = Some(index);
+        query_index += 1;
+    }
+    (query_index == query.len()).then_some(score)
+}
+
+pub fn filter<T>(query: &str, items: Vec<T>, label: impl Fn(&T) -> String) -> Vec<T> {
+    let mut scored: Vec<(i32, T)> = items
+        .into_iter()
+        .filter_map(|item| score(query, &label(&item)).map(|score| (score, item)))
+        .collect();
+    scored.sort_by_key(|s| std::cmp::Reverse(s.0));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert!(score("ffx", "Firefox").is_some());
+        assert!(score("FIRE", "firefox").is_some());
+        assert!(score("xf", "Firefox").is_none());
+        assert!(score("chromium", "Chrome").is_none());
+        assert_eq!(score("", "Safari"), Some(0));
+        assert_eq!(
+            score("arc work", "Arc · Work"),
+            score("arcwork", "Arc · Work")
+        );
+        assert!(score("chr", "Chrome").unwrap() > score("chr", "Arc Search").unwrap_or(0));
+        assert!(score("work", "Chrome · Work").unwrap() > score("work", "Firework").unwrap());
+    }
+
+    #[test]
+    fn test_filter() {
+        let items = vec!["Safari", "Firefox", "Chrome", "Chrome · Personal", "Arc"];
+        assert_eq!(
+            filter("chr", items.clone(), |item| item.to_string()),
+            vec!["Chrome", "Chrome · Personal"]
+        );
+        assert_eq!(
+            filter("per", items.clone(), |item| item.to_string()),
+            vec!["Chrome · Personal"]
+        );
+        assert_eq!(
+            filter("f", items.clone(), |item| item.to_string())[0],
+            "Firefox"
+        );
+        assert_eq!(filter("", items.clone(), |item| item.to_string()), items);
+        assert!(filter("zzz", items, |item| item.to_string()).is_empty());
+    }
+}
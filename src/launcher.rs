@@ -0,0 +1,463 @@
+pub struct LaunchOptions<'a> {
+    pub url: &'a str,
+    pub profile: Option<&'a str>,
+    pub private: bool,
+    pub new_window: bool,
+}
+
+pub trait Launcher {
+    fn app_args(&self, options: &LaunchOptions) -> Vec<String>;
+
+    fn supports_private(&self) -> bool {
+        true
+    }
+
+    fn command(&self, app_path: &str, options: &LaunchOptions) -> Vec<String> {
+        let args = self.app_args(options);
+        let mut command = vec!["open".to_string(), "-na".to_string(), app_path.to_string()];
+        if args.is_empty() {
+            command.push(options.url.to_string());
+        } else {
+            command.push("--args".to_string());
+            command.extend(args);
+        }
+        command
+    }
+}
+
+pub struct Chromium {
+    private_flag: &'static str,
+}
+
+impl Launcher for Chromium {
+    fn app_args(&self, options: &LaunchOptions) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(profile) = options.profile {
+            args.push(format!("--profile-directory={}", profile));
+        }
+        if options.private {
+            args.push(self.private_flag.to_string());
+        }
+        if options.new_window {
+            args.push("--new-window".to_string());
+        }
+        if !args.is_empty() {
+            args.push(options.url.to_string());
+        }
+        args
+    }
+}
+
+pub struct Firefox;
+
+impl Launcher for Firefox {
+    fn app_args(&self, options: &LaunchOptions) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(profile) = options.profile {
+            args.push("-P".to_string());
+            args.push(profile.to_string());
+        }
+        if options.private {
+            args.push("-private-window".to_string());
+        } else if options.new_window {
+            args.push("-new-window".to_string());
+        }
+        if !args.is_empty() {
+            args.push(options.url.to_string());
+        }
+        args
+    }
+}
+
+pub struct WebKit;
+
+impl Launcher for WebKit {
+    fn app_args(&self, _options: &LaunchOptions) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn supports_private(&self) -> bool {
+        false
+    }
+
+    fn command(&self, app_path: &str, options: &LaunchOptions) -> Vec<String> {
+        vec![
+            "open".to_string(),
+            "-a".to_string(),
+            app_path.to_string(),
+            options.url.to_string(),
+        ]
+    }
+}
+
+pub struct Generic;
+
+impl Launcher for Generic {
+    fn app_args(&self, _options: &LaunchOptions) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn supports_private(&self) -> bool {
+        false
+    }
+}
+
+const CHROMIUM: Chromium = Chromium {
+    private_flag: "--incognito",
+};
+const EDGE: Chromium = Chromium {
+    private_flag: "--inprivate",
+};
+const OPERA: Chromium = Chromium {
+    private_flag: "--private",
+};
+
+const CHROMIUM_BUNDLE_IDS: [&str; 7] = [
+    "com.google.Chrome",
+    "com.microsoft.edgemac",
+    "com.operasoftware.Opera",
+    "org.chromium.Chromium",
+    "com.brave.Browser",
+    "com.vivaldi.Vivaldi",
+    "com.naver.Whale",
+];
+const FIREFOX_BUNDLE_IDS: [&str; 6] = [
+    "org.mozilla.firefox",
+    "org.mozilla.nightly",
+    "org.torproject.torbrowser",
+    "io.gitlab.librewolf-community",
+    "net.waterfox.waterfox",
+    "app.zen-browser.zen",
+];
+const WEBKIT_BUNDLE_IDS: [&str; 3] = [
+    "com.apple.Safari",
+    "com.apple.SafariTechnologyPreview",
+    "com.kagi.kagimacOS",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowserFamily {
+    Chromium,
+    Firefox,
+    WebKit,
+    Generic,
+}
+
+pub fn family(bundle_id: &str) -> BrowserFamily {
+    let matches = |candidates: &[&str]| {
+        candidates
+            .iter()
+            .any(|candidate| bundle_id.starts_with(candidate))
+    };
+    if matches(&CHROMIUM_BUNDLE_IDS) {
+        BrowserFamily::Chromium
+    } else if matches(&FIREFOX_BUNDLE_IDS) {
+        BrowserFamily::Firefox
+    } else if matches(&WEBKIT_BUNDLE_IDS) {
+        BrowserFamily::WebKit
+    } else {
+        BrowserFamily::Generic
+    }
+}
+
+pub fn for_bundle_id(bundle_id: &str) -> &'static dyn Launcher {
+    match family(bundle_id) {
+        BrowserFamily::Chromium if bundle_id.starts_with("com.microsoft.edgemac") => &EDGE,
+        BrowserFamily::Chromium if bundle_id.starts_with("com.operasoftware.Opera") => &OPERA,
+        BrowserFamily::Chromium => &CHROMIUM,
+        BrowserFamily::Firefox => &Firefox,
+        BrowserFamily::WebKit => &WebKit,
+        BrowserFamily::Generic => &Generic,
+    }
+}
+
+pub fn split_command(template: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => arg.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            c @ ('"' | '\\' | '$' | '`') => arg.push(c),
+                            c => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                        },
+                        c => arg.push(c),
+                    }
+                }
+            }
+            '\\' => current.get_or_insert_with(String::new).push(chars.next()?),
+            c if c.is_whitespace() => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    (!args.is_empty()).then_some(args)
+}
+
+pub fn expand_command(template: &str, options: &LaunchOptions) -> Option<Vec<String>> {
+    let args = split_command(template)?;
+    let has_url = args.iter().any(|arg| arg.contains("{url}"));
+    let mut command: Vec<String> = args
+        .into_iter()
+        .filter(|arg| options.profile.is_some() || !arg.contains("{profile}"))
+        .map(|arg| substitute(&arg, options))
+        .collect();
+    if !has_url {
+        command.push(options.url.to_string());
+    }
+    Some(command)
+}
+
+fn substitute(arg: &str, options: &LaunchOptions) -> String {
+    let mut result = String::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(remaining) = rest.strip_prefix("{url}") {
+            result.push_str(options.url);
+            rest = remaining;
+        } else if let Some(remaining) = rest.strip_prefix("{profile}") {
+            result.push_str(options.profile.unwrap_or_default());
+            rest = remaining;
+        } else {
+            result.push('{');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com";
+
+    fn options(profile: Option<&str>, private: bool, new_window: bool) -> LaunchOptions<'_> {
+        LaunchOptions {
+            url: URL,
+            profile,
+            private,
+            new_window,
+        }
+    }
+
+    fn argv(bundle_id: &str, app_path: &str, options: LaunchOptions<'_>) -> Vec<String> {
+        for_bundle_id(bundle_id).command(app_path, &options)
+    }
+
+    #[test]
+    fn test_chromium_command() {
+        let app = "/Applications/Google Chrome.app";
+        assert_eq!(
+            argv("com.google.Chrome", app, options(None, false, false)),
+            vec!["open", "-na", app, URL]
+        );
+        assert_eq!(
+            argv(
+                "com.google.Chrome",
+                app,
+                options(Some("Profile 1"), false, false)
+            ),
+            vec![
+                "open",
+                "-na",
+                app,
+                "--args",
+                "--profile-directory=Profile 1",
+                URL
+            ]
+        );
+        assert_eq!(
+            argv(
+                "com.brave.Browser.beta",
+                app,
+                options(Some("Default"), true, true)
+            ),
+            vec![
+                "open",
+                "-na",
+                app,
+                "--args",
+                "--profile-directory=Default",
+                "--incognito",
+                "--new-window",
+                URL
+            ]
+        );
+        assert_eq!(
+            argv("com.microsoft.edgemac", app, options(None, true, false)),
+            vec!["open", "-na", app, "--args", "--inprivate", URL]
+        );
+        assert_eq!(
+            argv("com.operasoftware.Opera", app, options(None, true, false)),
+            vec!["open", "-na", app, "--args", "--private", URL]
+        );
+    }
+
+    #[test]
+    fn test_firefox_command() {
+        let app = "/Applications/Firefox.app";
+        assert_eq!(
+            argv("org.mozilla.firefox", app, options(None, false, false)),
+            vec!["open", "-na", app, URL]
+        );
+        assert_eq!(
+            argv(
+                "org.mozilla.firefox",
+                app,
+                options(Some("work"), false, false)
+            ),
+            vec!["open", "-na", app, "--args", "-P", "work", URL]
+        );
+        assert_eq!(
+            argv("org.mozilla.firefox", app, options(None, true, false)),
+            vec!["open", "-na", app, "--args", "-private-window", URL]
+        );
+        assert_eq!(
+            argv(
+                "org.mozilla.firefoxdeveloperedition",
+                app,
+                options(Some("dev"), false, true)
+            ),
+            vec![
+                "open",
+                "-na",
+                app,
+                "--args",
+                "-P",
+                "dev",
+                "-new-window",
+                URL
+            ]
+        );
+    }
+
+    #[test]
+    fn test_webkit_command() {
+        let app = "/Applications/Safari.app";
+        assert_eq!(
+            argv("com.apple.Safari", app, options(Some("Work"), true, true)),
+            vec!["open", "-a", app, URL]
+        );
+        assert!(!for_bundle_id("com.apple.Safari").supports_private());
+    }
+
+    #[test]
+    fn test_generic_command() {
+        let app = "/Applications/Unknown.app";
+        assert_eq!(
+            argv(
+                "com.example.unknown",
+                app,
+                options(Some("Work"), true, false)
+            ),
+            vec!["open", "-na", app, URL]
+        );
+        assert_eq!(
+            argv("", app, options(None, false, false)),
+            vec!["open", "-na", app, URL]
+        );
+        assert!(!for_bundle_id("com.example.unknown").supports_private());
+        assert_eq!(family("company.thebrowser.Browser"), BrowserFamily::Generic);
+        assert!(!for_bundle_id("company.thebrowser.Browser").supports_private());
+        assert!(for_bundle_id("com.google.Chrome").supports_private());
+        assert!(for_bundle_id("org.mozilla.firefox").supports_private());
+    }
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(
+            split_command("open -a 'Tor Browser' {url}"),
+            Some(vec![
+                "open".to_string(),
+                "-a".to_string(),
+                "Tor Browser".to_string(),
+                "{url}".to_string()
+            ])
+        );
+        assert_eq!(
+            split_command(r#"  ~/bin/vm\ open   "--name=\"dev box\"" \\x "" "#),
+            Some(vec![
+                "~/bin/vm open".to_string(),
+                "--name=\"dev box\"".to_string(),
+                "\\x".to_string(),
+                "".to_string()
+            ])
+        );
+        assert_eq!(split_command("open 'unterminated"), None);
+        assert_eq!(split_command("open \"unterminated"), None);
+        assert_eq!(split_command("trailing\\"), None);
+        assert_eq!(split_command("   "), None);
+    }
+
+    #[test]
+    fn test_expand_command() {
+        let url = "https://example.com/a b?q=1;rm -rf ~&x='y'";
+        let options = |profile| LaunchOptions {
+            url,
+            profile,
+            private: false,
+            new_window: false,
+        };
+        assert_eq!(
+            expand_command(
+                "/opt/chromium/chrome --profile-directory={profile} '{url}'",
+                &options(Some("Work 1"))
+            ),
+            Some(vec![
+                "/opt/chromium/chrome".to_string(),
+                "--profile-directory=Work 1".to_string(),
+                url.to_string()
+            ])
+        );
+        assert_eq!(
+            expand_command(
+                "/opt/chromium/chrome --profile-directory={profile} {url}",
+                &options(None)
+            ),
+            Some(vec!["/opt/chromium/chrome".to_string(), url.to_string()])
+        );
+        assert_eq!(
+            expand_command("vm-open --target dev", &options(None)),
+            Some(vec![
+                "vm-open".to_string(),
+                "--target".to_string(),
+                "dev".to_string(),
+                url.to_string()
+            ])
+        );
+        assert_eq!(
+            expand_command("echo {{url}} -P={profile}", &options(Some("{url}"))),
+            Some(vec![
+                "echo".to_string(),
+                format!("{{{}}}", url),
+                "-P={url}".to_string()
+            ])
+        );
+        assert_eq!(expand_command("'broken", &options(None)), None);
+    }
+}
@@ -1,61 +1,129 @@
+mod actions;
+mod app_links;
 mod config;
+mod domain;
+mod fuzzy;
 mod icons;
+mod launcher;
 mod message;
 mod pages;
 mod platform_tools;
+mod profiles;
+mod save_scope;
+mod settings;
 mod storage;
 mod subscriptions;
 
+use actions::Action;
 use config::{
-    LOG_DIR, LOG_FILE, MENU_WINDOW_HEIGHT, MENU_WINDOW_WIDTH, SETTING_WINDOW_HEIGHT,
-    SETTING_WINDOW_WIDTH,
+    HUD_WINDOW_HEIGHT, HUD_WINDOW_WIDTH, LOG_DIR, LOG_FILE, MENU_WINDOW_HEIGHT, MENU_WINDOW_WIDTH,
+    SETTING_WINDOW_HEIGHT, SETTING_WINDOW_WIDTH,
 };
+use domain::url_host;
 use iced::keyboard::Modifiers;
 use iced::widget::text_editor;
 use iced::window::Position;
 use iced::{
     event, keyboard,
-    widget::{text, Column},
+    widget::{container, text, Column},
     window, Element, Event, Font, Size, Subscription, Task, Theme,
 };
-use message::{ExternalOperation, Message, WindowType};
+use message::{
+    ExternalOperation, HistoryBrowser, HistoryRange, Message, RuleTarget, SettingTab, WindowType,
+};
+use pages::browser_manager::ManualBrowserForm;
 use pages::components::footer::footer;
+use pages::edit_match_value::MatchPreview;
+use pages::select_browser::PickerEntry;
 use platform_tools::{ensure_default_browser, get_mouse_position};
 use platform_tools::{open_url, show_app};
+use save_scope::SaveScope;
+use settings::{AutoRuleMode, Settings};
 use std::mem;
-use std::time::{Duration, Instant};
-use storage::{BrowserInfo, BrowserProfile, MatchItem, Storage};
-use subscriptions::tray_menu_event_subscription;
-use tracing::info;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use storage::{
+    match_precedence, BrowserInfo, BrowserProfile, HistoryEntry, HostChoice, HostStreak, MatchItem,
+    Storage, UrlStorage,
+};
+use subscriptions::{app_dirs_subscription, tray_menu_event_subscription};
+use tracing::{info, warn};
 use tracing_subscriber::fmt::format::FmtSpan;
 use tray_icon::menu::{Menu, MenuItem};
 use tray_icon::{TrayIcon, TrayIconBuilder};
 
 const IS_DEBUG: bool = cfg!(debug_assertions);
+const SUGGESTION_MIN_SCORE: f64 = 0.5;
+const ROUTE_HUD_DURATION: Duration = Duration::from_secs(4);
+const MATCH_PREVIEW_LIMIT: u32 = 5;
 
 struct MenuWindow {
     is_default_browser: bool,
     current_page: MenuWindowPage,
     browser_list: Vec<BrowserInfo>,
+    profiles: Vec<BrowserProfile>,
+    filter: String,
+    selected: usize,
+    save_scope: Option<SaveScope>,
+    url_editor: Option<UrlEditor>,
     launch_time: Instant,
     stacks: Vec<MenuWindowPage>,
     window_id: window::Id,
 }
 
+struct UrlEditor {
+    text: String,
+    original: String,
+}
+
 struct SettingWindow {
+    current_tab: SettingTab,
     launch_time: Instant,
     match_items: Vec<MatchItem>,
     window_id: window::Id,
     browser_list: Vec<BrowserInfo>,
+    search_path_text: String,
+    excluded_domain_text: String,
+    confirm_rule_hashing: bool,
+    manual_browser_form: ManualBrowserForm,
+    manual_bundle_ids: Vec<String>,
+    editing_browser_name: Option<(String, String)>,
+    orphaned_match_items: Vec<MatchItem>,
+    orphaned_profiles: Vec<BrowserProfile>,
+    rule_targets: Vec<RuleTarget>,
+    rule_target: Option<RuleTarget>,
+    history_query: String,
+    history_browser: HistoryBrowser,
+    history_range: HistoryRange,
+    history_entries: Vec<HistoryEntry>,
+}
+
+struct RouteHud {
+    window_id: window::Id,
+    url: String,
+    rule: MatchItem,
+}
+
+struct ReviewedRule {
+    rule: MatchItem,
+    bypassed: bool,
 }
 
 struct Gomi {
     _tray: TrayIcon,
     storage: Storage,
+    settings: Settings,
     keyboard: Modifiers,
     menu_window: Option<MenuWindow>,
     setting_window: Option<SettingWindow>,
     current_url: Option<String>,
+    url_source: Option<String>,
+    app_bundle_paths: Vec<PathBuf>,
+    removed_browsers: Vec<BrowserInfo>,
+    host_choices: Vec<HostChoice>,
+    rule_offer: Option<(String, HostStreak)>,
+    route_hud: Option<RouteHud>,
+    reviewed_rule: Option<ReviewedRule>,
 }
 #[derive(Debug)]
 enum MenuWindowPage {
@@ -67,14 +135,20 @@ enum MenuWindowPage {
     },
     MatchContainEditor {
         match_container_text: text_editor::Content,
-        browser_path: String,
+        bundle_id: String,
         profile: Option<String>,
+        private: bool,
+        replaces: Option<String>,
+        preview: MatchPreview,
     },
 }
 
 impl Gomi {
     fn new() -> (Self, Task<Message>) {
         let storage = Storage::new();
+        storage.resolve_bundle_ids(platform_tools::get_bundle_identifier);
+        let settings = Settings::load(&storage);
+        storage.set_privacy_policy(settings.privacy_policy(), unix_time());
         if IS_DEBUG {
             show_app();
         }
@@ -99,45 +173,102 @@ impl Gomi {
 
         (
             Self {
+                settings,
                 storage,
                 keyboard: Modifiers::default(),
                 menu_window: None,
                 setting_window: None,
                 current_url: None,
+                app_bundle_paths: Vec::new(),
+                removed_browsers: Vec::new(),
+                host_choices: Vec::new(),
+                rule_offer: None,
+                route_hud: None,
+                reviewed_rule: None,
+                url_source: None,
                 _tray: tray,
             },
-            if !ensure_default_browser() {
-                Task::done(Message::OpenWindow(WindowType::Menu))
-            } else {
-                Task::none()
-            },
+            Task::batch([
+                Task::done(Message::CheckInstalledApps),
+                if !ensure_default_browser() {
+                    Task::done(Message::OpenWindow(WindowType::Menu))
+                } else {
+                    Task::none()
+                },
+            ]),
         )
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
-        info!("update: {:?}", message);
+        if self.settings.url_storage == UrlStorage::FullUrl {
+            info!("update: {:?}", message);
+        } else {
+            let message_debug = format!("{:?}", message);
+            info!(
+                "update: {} (arguments redacted)",
+                message_debug
+                    .split(['(', ' ', '{'])
+                    .next()
+                    .unwrap_or_default()
+            );
+        }
         match message {
-            Message::LaunchBrowser(path, profile, external_operation) => {
-                if let Some(url) = self.current_url.clone() {
-                    open_url(url.clone(), path.clone(), profile.clone());
+            Message::LaunchBrowser(bundle_id, profile, private, external_operation) => {
+                if self.edited_url_invalid() {
+                    return Task::none();
+                }
+                if let Some((url, path)) =
+                    self.open_current_url(&bundle_id, profile.clone(), private, None)
+                {
+                    if let Some(host) = url_host(&url) {
+                        let streak = self.storage.record_host_choice(
+                            host.clone(),
+                            bundle_id.clone(),
+                            profile.clone(),
+                            unix_time(),
+                        );
+                        if external_operation.is_none()
+                            && self.settings.auto_rule_mode == AutoRuleMode::Create
+                            && streak >= self.settings.auto_rule_threshold
+                        {
+                            self.storage.insert_match(self.host_rule(
+                                &host,
+                                bundle_id.clone(),
+                                profile.clone(),
+                                path.clone(),
+                            ));
+                            self.storage.reset_host_streak(host);
+                        }
+                    }
                     if let Some(external_operation) = external_operation {
                         match external_operation {
-                            ExternalOperation::SaveEqual => {
-                                self.storage.insert_match(MatchItem {
-                                    browser_path: path.clone(),
-                                    profile: profile.clone(),
-                                    match_type: "Equal".to_string(),
-                                    match_value: url.clone(),
-                                });
+                            ExternalOperation::SaveRule(scope) => {
+                                if let Some((match_type, match_value)) = scope.rule(&url) {
+                                    self.storage.insert_match(MatchItem {
+                                        bundle_id: bundle_id.clone(),
+                                        browser_path: path.clone(),
+                                        profile: profile.clone(),
+                                        match_type: match_type.to_string(),
+                                        match_value,
+                                        private,
+                                        auto_created: false,
+                                    });
+                                }
                             }
                             ExternalOperation::SaveContain => {
                                 if let Some(menu_window) = &self.menu_window {
                                     if let MenuWindowPage::MatchContainEditor {
                                         match_container_text,
+                                        replaces,
                                         ..
                                     } = &menu_window.current_page
                                     {
+                                        if let Some(match_value) = replaces {
+                                            self.storage
+                                                .delete_match_by_match_value(match_value.clone());
+                                        }
                                         self.storage.insert_match(MatchItem {
+                                            bundle_id: bundle_id.clone(),
                                             browser_path: path.clone(),
                                             profile: profile.clone(),
                                             match_type: "Contain".to_string(),
@@ -145,6 +276,8 @@ impl Gomi {
                                                 .text()
                                                 .trim()
                                                 .to_string(),
+                                            private,
+                                            auto_created: false,
                                         });
                                     }
                                 }
@@ -155,27 +288,111 @@ impl Gomi {
                 }
                 Task::none()
             }
+            Message::RouteUrl(match_item) => {
+                if self
+                    .open_current_url(
+                        &match_item.bundle_id,
+                        match_item.profile.clone(),
+                        match_item.private,
+                        Some(&match_item),
+                    )
+                    .is_some()
+                {
+                    return Task::done(Message::CloseWindow(WindowType::Menu))
+                        .chain(self.show_route_hud(match_item));
+                }
+                Task::none()
+            }
+            Message::CloseRouteHud(id) => match self.route_hud.take() {
+                Some(hud) if hud.window_id == id => window::close(id),
+                hud => {
+                    self.route_hud = hud;
+                    Task::none()
+                }
+            },
+            Message::ChangeRoutedUrl => {
+                if let Some(hud) = self.route_hud.take() {
+                    self.host_choices = url_host(&hud.url)
+                        .map(|host| self.storage.get_host_choices(host, unix_time()))
+                        .unwrap_or_default();
+                    self.current_url = Some(hud.url);
+                    self.rule_offer = None;
+                    self.reviewed_rule = Some(ReviewedRule {
+                        rule: hud.rule,
+                        bypassed: false,
+                    });
+                    return window::close(hud.window_id)
+                        .chain(Task::done(Message::OpenWindow(WindowType::Menu)));
+                }
+                Task::none()
+            }
+            Message::EditReviewedRule => {
+                if let Some(ReviewedRule { rule, .. }) = self.reviewed_rule.take() {
+                    let text = if rule.match_type == "Equal" {
+                        self.current_url.clone().unwrap_or_default()
+                    } else {
+                        rule.match_value.clone()
+                    };
+                    let preview = self.match_preview(&text, Some(&rule.match_value));
+                    let new_page = MenuWindowPage::MatchContainEditor {
+                        match_container_text: text_editor::Content::with_text(&text),
+                        bundle_id: rule.bundle_id,
+                        profile: rule.profile,
+                        private: rule.private,
+                        replaces: Some(rule.match_value),
+                        preview,
+                    };
+                    if let Some(menu_window) = &mut self.menu_window {
+                        menu_window
+                            .stacks
+                            .push(mem::replace(&mut menu_window.current_page, new_page));
+                        menu_window.filter.clear();
+                        menu_window.selected = 0;
+                    }
+                }
+                Task::none()
+            }
+            Message::DeleteReviewedRule => {
+                if let Some(ReviewedRule { rule, .. }) = self.reviewed_rule.take() {
+                    self.storage.delete_match_by_match_value(rule.match_value);
+                    self.reload_setting_window();
+                }
+                Task::none()
+            }
 
             Message::ReceiveUrl(url) => {
                 self.current_url = Some(url.clone());
-                let equal_matched = self.storage.find_equal_matches_by_url(url.clone());
-                if let Some(match_item) = equal_matched {
-                    return Task::done(Message::LaunchBrowser(
-                        match_item.browser_path,
-                        match_item.profile,
-                        None,
-                    ));
-                }
-                let contain_matched = self.storage.find_contain_matches_by_url(url.clone());
-                if let Some(match_item) = contain_matched {
-                    return Task::done(Message::LaunchBrowser(
-                        match_item.browser_path,
-                        match_item.profile,
-                        None,
-                    ));
+                self.reviewed_rule = None;
+                self.url_source = platform_tools::get_frontmost_app_name();
+                self.host_choices = url_host(&url)
+                    .map(|host| self.storage.get_host_choices(host, unix_time()))
+                    .unwrap_or_default();
+                if let Some(match_item) = self.find_rule(&url) {
+                    if !self
+                        .settings
+                        .bypass_modifier
+                        .is_pressed(&platform_tools::get_current_modifiers())
+                    {
+                        return Task::done(Message::RouteUrl(match_item));
+                    }
+                    self.reviewed_rule = Some(ReviewedRule {
+                        rule: match_item,
+                        bypassed: true,
+                    });
                 }
-                if let Some(MenuWindow { window_id, .. }) = self.menu_window {
-                    return Task::done(Message::MoveWindow(window_id));
+                self.rule_offer = url_host(&url)
+                    .filter(|_| self.reviewed_rule.is_none())
+                    .filter(|_| self.settings.auto_rule_mode == AutoRuleMode::Offer)
+                    .and_then(|host| {
+                        let streak = self.storage.get_host_streak(host.clone())?;
+                        (streak.count >= self.settings.auto_rule_threshold
+                            && self.can_launch(&streak.bundle_id, &url))
+                        .then_some((host, streak))
+                    });
+                if let Some(menu_window) = &mut self.menu_window {
+                    menu_window.save_scope = None;
+                    menu_window.url_editor = None;
+                    return Task::done(Message::MoveWindow(menu_window.window_id));
                 }
 
                 Task::done(Message::OpenWindow(WindowType::Menu))
@@ -199,7 +416,7 @@ impl Gomi {
             }
             Message::ListProfiles(browser) => {
                 if let Some(menu_window) = &mut self.menu_window {
-                    let profiles = self.storage.get_browser_profiles(browser.path.clone());
+                    let profiles = self.storage.get_browser_profiles(browser.bundle_id.clone());
                     let new_page = MenuWindowPage::ProfileSelector {
                         profiles,
                         browser,
@@ -208,6 +425,8 @@ impl Gomi {
                     menu_window
                         .stacks
                         .push(mem::replace(&mut menu_window.current_page, new_page));
+                    menu_window.filter.clear();
+                    menu_window.selected = 0;
                 }
                 Task::none()
             }
@@ -219,13 +438,15 @@ impl Gomi {
                         ..
                     } = &menu_window.current_page
                     {
-                        self.storage
-                            .delete_browser_profile(browser.path.clone(), profile_name.clone());
-                        self.storage.delete_match_by_profile_and_browser_path(
-                            browser.path.clone(),
+                        self.storage.delete_browser_profile(
+                            browser.bundle_id.clone(),
                             profile_name.clone(),
                         );
-                        let profiles = self.storage.get_browser_profiles(browser.path.clone());
+                        self.storage.delete_match_by_profile_and_bundle_id(
+                            browser.bundle_id.clone(),
+                            profile_name.clone(),
+                        );
+                        let profiles = self.storage.get_browser_profiles(browser.bundle_id.clone());
                         menu_window.current_page = MenuWindowPage::ProfileSelector {
                             profiles,
                             browser: browser.clone(),
@@ -239,8 +460,11 @@ impl Gomi {
                 if let Some(menu_window) = &mut self.menu_window {
                     if let Some(page) = menu_window.stacks.pop() {
                         menu_window.current_page = page;
+                        menu_window.filter.clear();
+                        menu_window.selected = 0;
                     }
                 }
+                self.reload_profiles();
                 Task::none()
             }
             Message::AddProfile => {
@@ -255,11 +479,13 @@ impl Gomi {
                             return Task::none();
                         }
                         self.storage.insert_browser_profile(BrowserProfile {
+                            bundle_id: browser.bundle_id.clone(),
                             browser_path: browser.path.clone(),
                             profile: profile_text.clone(),
                             description: None,
+                            account: None,
                         });
-                        let profiles = self.storage.get_browser_profiles(browser.path.clone());
+                        let profiles = self.storage.get_browser_profiles(browser.bundle_id.clone());
                         menu_window.current_page = MenuWindowPage::ProfileSelector {
                             profiles,
                             browser: browser.clone(),
@@ -279,37 +505,179 @@ impl Gomi {
                 }
                 Task::none()
             }
-            Message::ShowMatchContainEditor(browser_path, profile) => {
-                if let Some(menu_window) = &mut self.menu_window {
-                    if let Some(url) = self.current_url.clone() {
+            Message::ShowMatchContainEditor(bundle_id, profile, private) => {
+                if self.edited_url_invalid() {
+                    return Task::none();
+                }
+                if let Some(url) = self.current_url.clone() {
+                    let preview = self.match_preview(&url, None);
+                    if let Some(menu_window) = &mut self.menu_window {
                         let new_page = MenuWindowPage::MatchContainEditor {
                             match_container_text: text_editor::Content::with_text(&url),
-                            browser_path,
+                            bundle_id,
                             profile,
+                            private,
+                            replaces: None,
+                            preview,
                         };
                         menu_window
                             .stacks
                             .push(mem::replace(&mut menu_window.current_page, new_page));
+                        menu_window.filter.clear();
+                        menu_window.selected = 0;
                     }
                 }
                 Task::none()
             }
-            Message::TypeMatchContainText(action) => {
-                if let Some(menu_window) = &mut self.menu_window {
-                    if let MenuWindowPage::MatchContainEditor {
-                        match_container_text,
-                        ..
-                    } = &mut menu_window.current_page
-                    {
-                        match_container_text.perform(action);
+            Message::ToggleUrlEditor => {
+                if let (Some(menu_window), Some(url)) = (&mut self.menu_window, &self.current_url) {
+                    menu_window.url_editor = match menu_window.url_editor {
+                        Some(_) => None,
+                        None => Some(UrlEditor {
+                            text: url.clone(),
+                            original: url.clone(),
+                        }),
+                    };
+                }
+                Task::none()
+            }
+            Message::EditUrl(text) => {
+                if let Some(url_editor) = self
+                    .menu_window
+                    .as_mut()
+                    .and_then(|menu_window| menu_window.url_editor.as_mut())
+                {
+                    url_editor.text = text.clone();
+                    if url::Url::parse(&text).is_ok() {
+                        self.set_current_url(text.trim().to_string());
                     }
                 }
                 Task::none()
             }
+            Message::ResetEditedUrl => {
+                if let Some(url_editor) = self
+                    .menu_window
+                    .as_mut()
+                    .and_then(|menu_window| menu_window.url_editor.as_mut())
+                {
+                    url_editor.text = url_editor.original.clone();
+                    let original = url_editor.original.clone();
+                    self.set_current_url(original);
+                }
+                Task::none()
+            }
+            Message::CopyUrl => {
+                let edited_url = self
+                    .menu_window
+                    .as_ref()
+                    .and_then(|menu_window| menu_window.url_editor.as_ref())
+                    .map(|url_editor| url_editor.text.clone());
+                match edited_url.or_else(|| self.current_url.clone()) {
+                    Some(url) => iced::clipboard::write(url),
+                    None => Task::none(),
+                }
+            }
+            Message::SelectSaveScope(scope) => {
+                if let Some(menu_window) = &mut self.menu_window {
+                    menu_window.save_scope =
+                        (menu_window.save_scope != Some(scope)).then_some(scope);
+                }
+                Task::none()
+            }
+            Message::TypeMatchContainText(action) => {
+                let Some(menu_window) = &mut self.menu_window else {
+                    return Task::none();
+                };
+                let MenuWindowPage::MatchContainEditor {
+                    match_container_text,
+                    replaces,
+                    ..
+                } = &mut menu_window.current_page
+                else {
+                    return Task::none();
+                };
+                match_container_text.perform(action);
+                let text = match_container_text.text();
+                let replaces = replaces.clone();
+                let new_preview = self.match_preview(&text, replaces.as_deref());
+                if let Some(MenuWindow {
+                    current_page: MenuWindowPage::MatchContainEditor { preview, .. },
+                    ..
+                }) = &mut self.menu_window
+                {
+                    *preview = new_preview;
+                }
+                Task::none()
+            }
             Message::KeyboardModifiersChanged(modifiers) => {
                 self.keyboard = modifiers;
                 Task::none()
             }
+            Message::KeyPressed(window_id, key) => {
+                let Some(menu_window) = &self.menu_window else {
+                    return Task::none();
+                };
+                if menu_window.window_id != window_id {
+                    return Task::none();
+                }
+                let entries = self.picker_entries();
+                let selected = menu_window.selected.min(entries.len().saturating_sub(1));
+                let searchable = menu_window.is_default_browser
+                    && matches!(menu_window.current_page, MenuWindowPage::Home);
+                let filtering = !menu_window.filter.is_empty();
+                match key.as_ref() {
+                    keyboard::Key::Named(keyboard::key::Named::Escape) if filtering => {
+                        self.set_picker_filter(String::new());
+                        Task::none()
+                    }
+                    keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                        Task::done(Message::CloseWindow(WindowType::Menu))
+                    }
+                    keyboard::Key::Named(keyboard::key::Named::Backspace) if filtering => {
+                        let mut filter = menu_window.filter.clone();
+                        filter.pop();
+                        self.set_picker_filter(filter);
+                        Task::none()
+                    }
+                    keyboard::Key::Named(keyboard::key::Named::Backspace) => {
+                        Task::done(Message::Back)
+                    }
+                    keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
+                        self.menu_window.as_mut().unwrap().selected =
+                            (selected + 1).min(entries.len().saturating_sub(1));
+                        Task::none()
+                    }
+                    keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
+                        self.menu_window.as_mut().unwrap().selected = selected.saturating_sub(1);
+                        Task::none()
+                    }
+                    keyboard::Key::Named(keyboard::key::Named::Enter) => entries
+                        .get(selected)
+                        .map(|entry| Task::done(self.activate_entry(entry)))
+                        .unwrap_or_else(Task::none),
+                    keyboard::Key::Named(keyboard::key::Named::Tab) => {
+                        match entries.get(selected) {
+                            Some(PickerEntry::Browser(browser)) => {
+                                Task::done(Message::ListProfiles(browser.clone()))
+                            }
+                            _ => Task::none(),
+                        }
+                    }
+                    keyboard::Key::Character(c) => match c.parse::<usize>() {
+                        Ok(number @ 1..=9) if !filtering => entries
+                            .get(number - 1)
+                            .map(|entry| Task::done(self.activate_entry(entry)))
+                            .unwrap_or_else(Task::none),
+                        _ if searchable && !self.keyboard.logo() && !self.keyboard.control() => {
+                            let filter = format!("{}{}", menu_window.filter, c);
+                            self.set_picker_filter(filter);
+                            Task::none()
+                        }
+                        _ => Task::none(),
+                    },
+                    _ => Task::none(),
+                }
+            }
             Message::OpenWindow(window_type) => {
                 if let Some(MenuWindow { window_id, .. }) = &self.menu_window {
                     if window_type == WindowType::Menu {
@@ -331,14 +699,22 @@ impl Gomi {
                     });
                     let mut browser_list = self.storage.get_browsers();
                     if browser_list.is_empty() {
-                        let handlers = platform_tools::get_url_handlers();
+                        let handlers =
+                            platform_tools::get_url_handlers(&self.settings.search_paths);
                         self.storage.batch_insert_browsers(handlers.clone());
+                        self.discover_profiles();
                         browser_list = handlers;
                     }
+                    let profiles = self.load_profiles(&browser_list);
                     self.menu_window = Some(MenuWindow {
                         is_default_browser: IS_DEBUG || platform_tools::ensure_default_browser(),
                         current_page: MenuWindowPage::Home,
                         browser_list: browser_list,
+                        profiles,
+                        filter: String::new(),
+                        selected: 0,
+                        save_scope: None,
+                        url_editor: None,
                         launch_time: Instant::now(),
                         stacks: vec![],
                         window_id: id,
@@ -349,14 +725,28 @@ impl Gomi {
                         size: Size::new(SETTING_WINDOW_WIDTH, SETTING_WINDOW_HEIGHT),
                         ..Default::default()
                     });
-                    let match_items = self.storage.find_all_match_items();
-                    let browser_list = self.storage.get_browsers();
                     self.setting_window = Some(SettingWindow {
+                        current_tab: SettingTab::Rules,
                         launch_time: Instant::now(),
-                        match_items,
-                        browser_list,
+                        match_items: vec![],
+                        browser_list: vec![],
                         window_id: id,
+                        search_path_text: String::new(),
+                        excluded_domain_text: String::new(),
+                        confirm_rule_hashing: false,
+                        manual_browser_form: ManualBrowserForm::default(),
+                        manual_bundle_ids: vec![],
+                        editing_browser_name: None,
+                        orphaned_match_items: vec![],
+                        orphaned_profiles: vec![],
+                        rule_targets: vec![],
+                        rule_target: None,
+                        history_query: String::new(),
+                        history_browser: HistoryBrowser::default(),
+                        history_range: HistoryRange::default(),
+                        history_entries: vec![],
                     });
+                    self.reload_setting_window();
                     (open, id)
                 };
                 open.then(move |_| window::gain_focus::<Message>(id))
@@ -382,6 +772,7 @@ impl Gomi {
                 if let Some(MenuWindow { window_id, .. }) = self.menu_window {
                     if window == window_id {
                         self.menu_window = None;
+                        self.reviewed_rule = None;
                     }
                 }
                 if let Some(SettingWindow { window_id, .. }) = self.setting_window {
@@ -389,6 +780,11 @@ impl Gomi {
                         self.setting_window = None;
                     }
                 }
+                if let Some(RouteHud { window_id, .. }) = self.route_hud {
+                    if window == window_id {
+                        self.route_hud = None;
+                    }
+                }
                 Task::none()
             }
             Message::WindowUnfocused(window) => {
@@ -420,30 +816,990 @@ impl Gomi {
             }
 
             Message::RefreshBrowserList => {
-                self.storage.delete_all_browsers();
-                let browsers = platform_tools::get_url_handlers();
-                self.storage.batch_insert_browsers(browsers.clone());
+                let browsers = platform_tools::get_url_handlers(&self.settings.search_paths);
+                let removed = self.storage.reconcile_browsers(browsers);
+                self.storage
+                    .resolve_bundle_ids(platform_tools::get_bundle_identifier);
+                self.discover_profiles();
+                let match_items = self.storage.find_all_match_items();
+                for browser in removed {
+                    if match_items
+                        .iter()
+                        .any(|item| item.bundle_id == browser.bundle_id)
+                    {
+                        warn!(
+                            "browser {} removed while rules target it",
+                            browser.bundle_id
+                        );
+                        self.removed_browsers.push(browser);
+                    }
+                }
+                let missing_browsers = self.storage.get_missing_browsers();
+                self.removed_browsers
+                    .retain(|browser| missing_browsers.contains(browser));
                 if let Some(menu_window) = &mut self.menu_window {
-                    menu_window.browser_list = browsers;
+                    menu_window.browser_list = self.storage.get_browsers();
                 }
+                self.reload_profiles();
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::CheckInstalledApps => {
+                let app_bundle_paths = platform_tools::app_dirs::find_app_bundles(
+                    &platform_tools::app_dirs::search_dirs(&self.settings.search_paths),
+                );
+                if app_bundle_paths == self.app_bundle_paths {
+                    return Task::none();
+                }
+                self.app_bundle_paths = app_bundle_paths;
+                Task::done(Message::RefreshBrowserList)
+            }
+            Message::DismissRemovedBrowsers => {
+                self.removed_browsers.clear();
                 Task::none()
             }
             Message::CloseApplication => iced::exit(),
             Message::DeleteMatchItem(match_value) => {
                 self.storage
                     .delete_match_by_match_value(match_value.clone());
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::ToggleMatchPrivate(match_value, private) => {
+                self.storage.set_match_private(match_value, private);
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::SelectRuleTarget(target) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.rule_target = Some(target);
+                }
+                Task::none()
+            }
+            Message::ReassignOrphanedRules => {
+                if let Some(setting_window) = &self.setting_window {
+                    if let Some(target) = setting_window.rule_target.clone() {
+                        let match_values = setting_window
+                            .orphaned_match_items
+                            .iter()
+                            .map(|item| item.match_value.clone())
+                            .collect();
+                        self.storage.reassign_matches(
+                            match_values,
+                            target.bundle_id,
+                            target.browser_path,
+                            target.profile,
+                        );
+                    }
+                }
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::DeleteOrphanedRules => {
+                if let Some(setting_window) = &self.setting_window {
+                    for item in &setting_window.orphaned_match_items {
+                        self.storage
+                            .delete_match_by_match_value(item.match_value.clone());
+                    }
+                }
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::DeleteOrphanedProfiles => {
+                self.storage.delete_orphaned_browser_profiles();
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::SwitchSettingTab(tab) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.current_tab = tab;
+                }
+                self.reload_history();
+                Task::none()
+            }
+            Message::SearchHistory(query) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.history_query = query;
+                }
+                self.reload_history();
+                Task::none()
+            }
+            Message::FilterHistoryBrowser(browser) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.history_browser = browser;
+                }
+                self.reload_history();
+                Task::none()
+            }
+            Message::FilterHistoryRange(range) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.history_range = range;
+                }
+                self.reload_history();
+                Task::none()
+            }
+            Message::ReopenHistoryEntry(entry, target) => {
+                if self
+                    .open_with(&entry.url, &target.bundle_id, target.profile.clone(), false)
+                    .is_some()
+                {
+                    self.record_history(
+                        entry.url,
+                        target.bundle_id,
+                        target.profile,
+                        None,
+                        Some("History".to_string()),
+                    );
+                    self.reload_history();
+                }
+                Task::none()
+            }
+            Message::HistoryEntryToRule(entry) => {
+                let browser_path = self
+                    .resolve_browser_path(&entry.bundle_id)
+                    .unwrap_or_default();
+                let rule = match url_host(&entry.url) {
+                    Some(host) => MatchItem {
+                        auto_created: false,
+                        ..self.host_rule(&host, entry.bundle_id, entry.profile, browser_path)
+                    },
+                    None => MatchItem {
+                        bundle_id: entry.bundle_id,
+                        browser_path,
+                        profile: entry.profile,
+                        match_type: "Equal".to_string(),
+                        match_value: entry.url,
+                        private: false,
+                        auto_created: false,
+                    },
+                };
+                self.storage.insert_match(rule);
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::SetPrivateModifier(key) => {
+                self.settings.set_private_modifier(&self.storage, key);
+                Task::none()
+            }
+            Message::SetBypassModifier(key) => {
+                self.settings.set_bypass_modifier(&self.storage, key);
+                Task::none()
+            }
+            Message::SetReadingListPath(path) => {
+                self.settings.set_reading_list_path(&self.storage, path);
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::SetScriptCommand(command) => {
+                self.settings.set_script_command(&self.storage, command);
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::AcceptRuleOffer => {
+                if let Some((host, streak)) = self.rule_offer.take() {
+                    let path = self
+                        .resolve_browser_path(&streak.bundle_id)
+                        .unwrap_or_default();
+                    let rule = self.host_rule(&host, streak.bundle_id, streak.profile, path);
+                    self.storage.insert_match(rule.clone());
+                    self.storage.reset_host_streak(host);
+                    return Task::done(Message::RouteUrl(rule));
+                }
+                Task::none()
+            }
+            Message::DismissRuleOffer => {
+                if let Some((host, _)) = self.rule_offer.take() {
+                    self.storage.reset_host_streak(host);
+                }
+                Task::none()
+            }
+            Message::SetAutoRuleMode(mode) => {
+                self.settings.set_auto_rule_mode(&self.storage, mode);
+                Task::none()
+            }
+            Message::SetAutoRuleThreshold(threshold) => {
+                self.settings
+                    .set_auto_rule_threshold(&self.storage, threshold);
+                Task::none()
+            }
+            Message::SetAutoRuleScope(scope) => {
+                self.settings.set_auto_rule_scope(&self.storage, scope);
+                Task::none()
+            }
+            Message::ClearUsageHistory => {
+                self.storage.clear_host_choices();
+                self.host_choices.clear();
+                Task::none()
+            }
+            Message::SetHistoryRetention(retention) => {
+                self.settings
+                    .set_history_retention(&self.storage, retention);
+                self.apply_privacy_policy();
+                Task::none()
+            }
+            Message::SetUrlStorage(url_storage) => {
+                self.settings.set_url_storage(&self.storage, url_storage);
+                self.apply_privacy_policy();
+                Task::none()
+            }
+            Message::SetHashExactRules(hash_exact_rules) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.confirm_rule_hashing =
+                        hash_exact_rules && !self.settings.hash_exact_rules;
+                }
+                if !hash_exact_rules {
+                    self.settings.set_hash_exact_rules(&self.storage, false);
+                    self.apply_privacy_policy();
+                }
+                Task::none()
+            }
+            Message::ConfirmHashExactRules => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.confirm_rule_hashing = false;
+                }
+                self.settings.set_hash_exact_rules(&self.storage, true);
+                self.apply_privacy_policy();
+                Task::none()
+            }
+            Message::TypeExcludedDomain(text) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.excluded_domain_text = text;
+                }
+                Task::none()
+            }
+            Message::AddExcludedDomain => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    let domain = mem::take(&mut setting_window.excluded_domain_text);
+                    self.settings.add_excluded_domain(&self.storage, domain);
+                }
+                self.apply_privacy_policy();
+                Task::none()
+            }
+            Message::RemoveExcludedDomain(domain) => {
+                self.settings.remove_excluded_domain(&self.storage, &domain);
+                self.apply_privacy_policy();
+                Task::none()
+            }
+            Message::PurgeHistory => {
+                self.storage.purge_history();
+                self.host_choices.clear();
+                self.rule_offer = None;
+                self.reload_history();
+                Task::none()
+            }
+            Message::SetInlineProfiles(inline_profiles) => {
+                self.settings
+                    .set_inline_profiles(&self.storage, inline_profiles);
+                Task::none()
+            }
+            Message::TypeSearchPathText(text) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.search_path_text = text;
+                }
+                Task::none()
+            }
+            Message::AddSearchPath => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    let path = setting_window.search_path_text.trim().to_string();
+                    if path.is_empty() {
+                        return Task::none();
+                    }
+                    self.settings.add_search_path(&self.storage, path);
+                    setting_window.search_path_text = String::new();
+                    return Task::done(Message::RefreshBrowserList);
+                }
+                Task::none()
+            }
+            Message::RemoveSearchPath(path) => {
+                self.settings.remove_search_path(&self.storage, &path);
+                Task::done(Message::RefreshBrowserList)
+            }
+            Message::TypeManualAppPath(text) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.manual_browser_form.app_path = text;
+                }
+                Task::none()
+            }
+            Message::AddManualApp => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    let form = &mut setting_window.manual_browser_form;
+                    let path = platform_tools::app_dirs::expand_home(form.app_path.trim());
+                    match platform_tools::read_app_browser(&path.to_string_lossy()) {
+                        Some(browser) => {
+                            self.storage.insert_manual_browser(browser);
+                            *form = ManualBrowserForm::default();
+                        }
+                        None => {
+                            form.error =
+                                Some(format!("No application found at {}", path.display()));
+                        }
+                    }
+                }
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::TypeCustomBrowserName(text) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.manual_browser_form.name = text;
+                }
+                Task::none()
+            }
+            Message::TypeCustomCommand(text) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.manual_browser_form.command = text;
+                }
+                Task::none()
+            }
+            Message::AddCustomCommand => {
                 if let Some(setting_window) = &mut self.setting_window {
-                    setting_window.match_items = self.storage.find_all_match_items();
+                    let form = &mut setting_window.manual_browser_form;
+                    let name = form.name.trim().to_string();
+                    let command = form.command.trim().to_string();
+                    if name.is_empty() {
+                        form.error = Some("Give the command a name".to_string());
+                        return Task::none();
+                    }
+                    let Some(args) = launcher::split_command(&command) else {
+                        form.error =
+                            Some("The command is empty or has an unclosed quote".to_string());
+                        return Task::none();
+                    };
+                    let slug: String = name
+                        .to_lowercase()
+                        .chars()
+                        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                        .collect();
+                    let mut bundle_id = format!("custom.{}", slug);
+                    let mut suffix = 2;
+                    while self.storage.is_bundle_id_used(&bundle_id) {
+                        bundle_id = format!("custom.{}-{}", slug, suffix);
+                        suffix += 1;
+                    }
+                    self.storage.insert_manual_browser(BrowserInfo {
+                        bundle_id,
+                        name,
+                        path: args[0].clone(),
+                        icon_data: vec![],
+                        launch_command: Some(command),
+                        hidden: false,
+                    });
+                    *form = ManualBrowserForm::default();
                 }
+                self.reload_setting_window();
                 Task::none()
             }
+            Message::RemoveManualBrowser(bundle_id) => {
+                self.storage.delete_manual_browser(bundle_id);
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::ToggleBrowserHidden(bundle_id, hidden) => {
+                self.storage.set_browser_hidden(bundle_id, hidden);
+                self.reload_setting_window();
+                Task::none()
+            }
+            Message::TypeBrowserName(bundle_id, text) => {
+                if let Some(setting_window) = &mut self.setting_window {
+                    setting_window.editing_browser_name = Some((bundle_id, text));
+                }
+                Task::none()
+            }
+            Message::RenameBrowser => {
+                if let Some((bundle_id, name)) = self
+                    .setting_window
+                    .as_mut()
+                    .and_then(|setting_window| setting_window.editing_browser_name.take())
+                {
+                    let name = name.trim().to_string();
+                    self.storage
+                        .rename_browser(bundle_id, (!name.is_empty()).then_some(name));
+                    self.reload_setting_window();
+                }
+                Task::none()
+            }
+            Message::MoveBrowser(bundle_id, offset) => {
+                if let Some(setting_window) = &self.setting_window {
+                    let mut bundle_ids: Vec<String> = setting_window
+                        .browser_list
+                        .iter()
+                        .map(|browser| browser.bundle_id.clone())
+                        .collect();
+                    if let Some(index) = bundle_ids.iter().position(|id| *id == bundle_id) {
+                        let target = index.saturating_add_signed(offset);
+                        if target < bundle_ids.len() {
+                            bundle_ids.swap(index, target);
+                            self.storage.set_browser_order(bundle_ids);
+                            self.reload_setting_window();
+                        }
+                    }
+                }
+                Task::none()
+            }
+        }
+    }
+
+    fn visible_browsers(&self, browser_list: &[BrowserInfo]) -> Vec<BrowserInfo> {
+        let current_url = self.current_url.clone().unwrap_or_default();
+        browser_list
+            .iter()
+            .filter(|browser| {
+                !browser.hidden && app_links::can_open(&browser.bundle_id, &current_url)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn picker_entries(&self) -> Vec<PickerEntry> {
+        let Some(menu_window) = &self.menu_window else {
+            return vec![];
+        };
+        match &menu_window.current_page {
+            MenuWindowPage::Home if menu_window.is_default_browser => {
+                let filter = &menu_window.filter;
+                let show_profiles = self.settings.inline_profiles || !filter.is_empty();
+                let mut entries = vec![];
+                for browser in self.visible_browsers(&menu_window.browser_list) {
+                    entries.push(PickerEntry::Browser(browser.clone()));
+                    if show_profiles {
+                        entries.extend(
+                            menu_window
+                                .profiles
+                                .iter()
+                                .filter(|profile| profile.bundle_id == browser.bundle_id)
+                                .map(|profile| {
+                                    PickerEntry::Profile(browser.clone(), profile.clone())
+                                }),
+                        );
+                    }
+                }
+                entries.extend(self.actions().into_iter().map(PickerEntry::Action));
+                if !filter.is_empty() {
+                    return fuzzy::filter(filter, entries, |entry| entry.label());
+                }
+                if let Some(choice) = self
+                    .host_choices
+                    .first()
+                    .filter(|choice| choice.score >= SUGGESTION_MIN_SCORE)
+                {
+                    // Surface a habitual profile even when profiles aren't listed inline.
+                    let target = (choice.bundle_id.clone(), choice.profile.clone());
+                    let browser =
+                        entries
+                            .iter()
+                            .enumerate()
+                            .find_map(|(index, entry)| match entry {
+                                PickerEntry::Browser(browser)
+                                    if browser.bundle_id == choice.bundle_id =>
+                                {
+                                    Some((index, browser.clone()))
+                                }
+                                _ => None,
+                            });
+                    let profile = menu_window.profiles.iter().find(|profile| {
+                        profile.bundle_id == choice.bundle_id
+                            && Some(&profile.profile) == choice.profile.as_ref()
+                    });
+                    if let (Some((index, browser)), Some(profile)) = (browser, profile) {
+                        if !entries.iter().any(|entry| entry.target() == target) {
+                            entries
+                                .insert(index + 1, PickerEntry::Profile(browser, profile.clone()));
+                        }
+                    }
+                }
+                entries.sort_by(|a, b| self.choice_score(b).total_cmp(&self.choice_score(a)));
+                entries
+            }
+            MenuWindowPage::ProfileSelector {
+                browser, profiles, ..
+            } => profiles
+                .iter()
+                .map(|profile| PickerEntry::Profile(browser.clone(), profile.clone()))
+                .collect(),
+            _ => vec![],
         }
     }
 
+    fn choice_score(&self, entry: &PickerEntry) -> f64 {
+        let (bundle_id, profile) = entry.target();
+        self.host_choices
+            .iter()
+            .find(|choice| choice.bundle_id == bundle_id && choice.profile == profile)
+            .map(|choice| choice.score)
+            .unwrap_or_default()
+    }
+
+    fn suggested_target(&self, entries: &[PickerEntry]) -> Option<(String, Option<String>)> {
+        self.host_choices
+            .iter()
+            .take_while(|choice| choice.score >= SUGGESTION_MIN_SCORE)
+            .map(|choice| (choice.bundle_id.clone(), choice.profile.clone()))
+            .find(|target| entries.iter().any(|entry| entry.target() == *target))
+    }
+
+    fn activate_entry(&self, entry: &PickerEntry) -> Message {
+        match entry {
+            PickerEntry::Browser(browser) => self.launch_message(browser.bundle_id.clone(), None),
+            PickerEntry::Profile(browser, profile) => {
+                self.launch_message(browser.bundle_id.clone(), Some(profile.profile.clone()))
+            }
+            PickerEntry::Action(action) => self.launch_message(action.id().to_string(), None),
+        }
+    }
+
+    fn launch_message(&self, bundle_id: String, profile: Option<String>) -> Message {
+        let private = self.settings.private_modifier.is_pressed(&self.keyboard);
+        let save_scope = self
+            .menu_window
+            .as_ref()
+            .and_then(|menu_window| menu_window.save_scope);
+        match save_scope {
+            Some(SaveScope::Custom) => Message::ShowMatchContainEditor(bundle_id, profile, private),
+            Some(scope) => Message::LaunchBrowser(
+                bundle_id,
+                profile,
+                private,
+                Some(ExternalOperation::SaveRule(scope)),
+            ),
+            None => Message::LaunchBrowser(bundle_id, profile, private, None),
+        }
+    }
+
+    fn edited_url_invalid(&self) -> bool {
+        self.menu_window
+            .as_ref()
+            .and_then(|menu_window| menu_window.url_editor.as_ref())
+            .is_some_and(|url_editor| url::Url::parse(&url_editor.text).is_err())
+    }
+
+    fn private_unsupported_text(&self) -> Option<String> {
+        if !self.settings.private_modifier.is_pressed(&self.keyboard) {
+            return None;
+        }
+        let names: Vec<String> = self
+            .menu_window
+            .as_ref()?
+            .browser_list
+            .iter()
+            .filter(|browser| {
+                !browser.hidden
+                    && (browser.launch_command.is_some()
+                        || (!actions::is_action(&browser.bundle_id)
+                            && app_links::find_by_bundle_id(&browser.bundle_id).is_none()
+                            && !launcher::for_bundle_id(&browser.bundle_id).supports_private()))
+            })
+            .map(|browser| browser.name.clone())
+            .collect();
+        (!names.is_empty()).then(|| {
+            format!(
+                "No private window in {}; it opens normally",
+                names.join(", ")
+            )
+        })
+    }
+
+    fn set_picker_filter(&mut self, filter: String) {
+        if let Some(menu_window) = &mut self.menu_window {
+            menu_window.filter = filter;
+            menu_window.selected = 0;
+        }
+    }
+
+    fn discover_profiles(&self) {
+        for browser in self.storage.get_browsers() {
+            self.storage
+                .insert_discovered_profiles(profiles::discover(&browser.path, &browser.bundle_id));
+        }
+    }
+
+    fn load_profiles(&self, browser_list: &[BrowserInfo]) -> Vec<BrowserProfile> {
+        browser_list
+            .iter()
+            .filter(|browser| !browser.hidden)
+            .flat_map(|browser| self.storage.get_browser_profiles(browser.bundle_id.clone()))
+            .collect()
+    }
+
+    fn reload_profiles(&mut self) {
+        if let Some(menu_window) = &self.menu_window {
+            let profiles = self.load_profiles(&menu_window.browser_list);
+            self.menu_window.as_mut().unwrap().profiles = profiles;
+        }
+    }
+
+    fn reload_setting_window(&mut self) {
+        let actions = self.actions();
+        if let Some(setting_window) = &mut self.setting_window {
+            setting_window.match_items = self.storage.find_all_match_items();
+            setting_window.browser_list = self.storage.get_browsers();
+            setting_window.manual_bundle_ids = self
+                .storage
+                .get_manual_browsers()
+                .into_iter()
+                .map(|browser| browser.bundle_id)
+                .collect();
+            setting_window.orphaned_match_items = self.storage.find_orphaned_match_items();
+            setting_window.orphaned_profiles = self.storage.find_orphaned_browser_profiles();
+            setting_window.rule_targets = setting_window
+                .browser_list
+                .iter()
+                .flat_map(|browser| {
+                    let browser_target = RuleTarget {
+                        bundle_id: browser.bundle_id.clone(),
+                        browser_path: browser.path.clone(),
+                        name: browser.name.clone(),
+                        profile: None,
+                        profile_name: None,
+                    };
+                    let profile_targets = self
+                        .storage
+                        .get_browser_profiles(browser.bundle_id.clone())
+                        .into_iter()
+                        .map(|profile| RuleTarget {
+                            profile_name: Some(
+                                profile.description.unwrap_or(profile.profile.clone()),
+                            ),
+                            profile: Some(profile.profile),
+                            ..browser_target.clone()
+                        });
+                    std::iter::once(browser_target.clone()).chain(profile_targets)
+                })
+                .chain(actions.iter().map(|action| RuleTarget {
+                    bundle_id: action.id().to_string(),
+                    browser_path: String::new(),
+                    name: action.name().to_string(),
+                    profile: None,
+                    profile_name: None,
+                }))
+                .collect();
+            if !setting_window
+                .rule_targets
+                .iter()
+                .any(|target| Some(target) == setting_window.rule_target.as_ref())
+            {
+                setting_window.rule_target = None;
+            }
+        }
+        self.reload_history();
+    }
+
+    fn apply_privacy_policy(&mut self) {
+        self.storage
+            .set_privacy_policy(self.settings.privacy_policy(), unix_time());
+        self.reload_setting_window();
+    }
+
+    fn reload_history(&mut self) {
+        if let Some(setting_window) = &mut self.setting_window {
+            if setting_window.current_tab != SettingTab::History {
+                return;
+            }
+            setting_window.history_entries = self.storage.search_history(
+                &setting_window.history_query,
+                setting_window.history_browser.bundle_id.clone(),
+                setting_window.history_range.since(unix_time()),
+            );
+        }
+    }
+
+    fn record_history(
+        &self,
+        url: String,
+        bundle_id: String,
+        profile: Option<String>,
+        matched: Option<&MatchItem>,
+        source: Option<String>,
+    ) {
+        self.storage.insert_history(HistoryEntry {
+            id: 0,
+            url,
+            opened_at: unix_time(),
+            source,
+            match_type: matched.map(|item| item.match_type.clone()),
+            match_value: matched.map(|item| item.match_value.clone()),
+            bundle_id,
+            profile,
+        });
+    }
+
+    fn host_rule(
+        &self,
+        host: &str,
+        bundle_id: String,
+        profile: Option<String>,
+        browser_path: String,
+    ) -> MatchItem {
+        let scope = self.settings.auto_rule_scope;
+        MatchItem {
+            bundle_id,
+            browser_path,
+            profile,
+            match_type: scope.match_type().to_string(),
+            match_value: scope.match_value(host),
+            private: false,
+            auto_created: true,
+        }
+    }
+
+    fn target_label(&self, bundle_id: &str, profile: &Option<String>) -> String {
+        let name = self
+            .storage
+            .get_browser(bundle_id.to_string())
+            .map(|browser| browser.name)
+            .or_else(|| actions::display_name(bundle_id).map(str::to_string))
+            .unwrap_or_else(|| bundle_id.to_string());
+        match profile {
+            Some(profile) => format!("{} ({})", name, profile),
+            None => name,
+        }
+    }
+
+    fn find_rule(&self, url: &str) -> Option<MatchItem> {
+        let equal_matched = self
+            .storage
+            .find_equal_matches_by_url(url.to_string())
+            .filter(|item| self.can_launch(&item.bundle_id, url));
+        let prefix_matched = || {
+            self.storage
+                .find_prefix_matches_by_url(url.to_string())
+                .filter(|item| self.can_launch(&item.bundle_id, url))
+        };
+        let contain_matched = || {
+            self.storage
+                .find_contain_matches_by_url(url.to_string())
+                .filter(|item| self.can_launch(&item.bundle_id, url))
+        };
+        let host_matched = || {
+            url_host(url)
+                .and_then(|host| self.storage.find_host_matches(host))
+                .filter(|item| self.can_launch(&item.bundle_id, url))
+        };
+        equal_matched
+            .or_else(prefix_matched)
+            .or_else(contain_matched)
+            .or_else(host_matched)
+    }
+
+    fn set_current_url(&mut self, url: String) {
+        self.host_choices = url_host(&url)
+            .map(|host| self.storage.get_host_choices(host, unix_time()))
+            .unwrap_or_default();
+        self.rule_offer = None;
+        if let Some(menu_window) = &mut self.menu_window {
+            menu_window.save_scope = menu_window
+                .save_scope
+                .filter(|scope| SaveScope::available(&url).contains(scope));
+        }
+        self.current_url = Some(url);
+    }
+
+    fn match_preview(&self, pattern: &str, replaces: Option<&str>) -> MatchPreview {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return MatchPreview::default();
+        }
+        let current_url = self.current_url.clone().unwrap_or_default();
+        let matches_current_url = current_url.contains(pattern);
+        let (history_count, history_urls) = self
+            .storage
+            .find_history_urls_containing(pattern, MATCH_PREVIEW_LIMIT);
+        let mut samples = history_urls.clone();
+        if matches_current_url && !samples.contains(&current_url) {
+            samples.insert(0, current_url);
+        }
+        let contain_precedence = match_precedence("Contain");
+        let mut overrides = Vec::new();
+        let mut overridden_by = Vec::new();
+        for rule in samples
+            .iter()
+            .flat_map(|url| self.storage.find_rules_matching(url))
+        {
+            if Some(rule.match_value.as_str()) == replaces {
+                continue;
+            }
+            let label = format!(
+                "{} → {}",
+                Self::rule_name(&rule),
+                self.target_label(&rule.bundle_id, &rule.profile)
+            );
+            let list = if rule.precedence() <= contain_precedence {
+                &mut overridden_by
+            } else {
+                &mut overrides
+            };
+            if !list.contains(&label) {
+                list.push(label);
+            }
+        }
+        MatchPreview {
+            matches_current_url,
+            history_count,
+            history_urls,
+            overrides,
+            overridden_by,
+        }
+    }
+
+    fn rule_name(rule: &MatchItem) -> String {
+        if rule.match_type == "Equal" {
+            "an exact-URL rule".to_string()
+        } else {
+            format!("rule \"{}\"", rule.match_value)
+        }
+    }
+
+    fn routed_text(&self, rule: &MatchItem) -> String {
+        format!(
+            "Opened in {} via {}",
+            self.target_label(&rule.bundle_id, &rule.profile),
+            Self::rule_name(rule)
+        )
+    }
+
+    fn review_text(&self, reviewed: &ReviewedRule) -> String {
+        if reviewed.bypassed {
+            format!(
+                "Skipped {}, which opens this link in {}. Pick a browser, or change the rule.",
+                Self::rule_name(&reviewed.rule),
+                self.target_label(&reviewed.rule.bundle_id, &reviewed.rule.profile)
+            )
+        } else {
+            format!(
+                "{}. Pick another browser to reopen the link, or change the rule.",
+                self.routed_text(&reviewed.rule)
+            )
+        }
+    }
+
+    fn show_route_hud(&mut self, rule: MatchItem) -> Task<Message> {
+        let close_previous = match self.route_hud.take() {
+            Some(hud) => window::close(hud.window_id),
+            None => Task::none(),
+        };
+        let (id, open) = window::open(window::Settings {
+            position: Position::Specific(get_mouse_position()),
+            size: Size::new(HUD_WINDOW_WIDTH, HUD_WINDOW_HEIGHT),
+            decorations: false,
+            resizable: false,
+            level: window::Level::AlwaysOnTop,
+            ..Default::default()
+        });
+        self.route_hud = Some(RouteHud {
+            window_id: id,
+            url: self.current_url.clone().unwrap_or_default(),
+            rule,
+        });
+        Task::batch([
+            close_previous,
+            open.then(|_| Task::none()),
+            Task::perform(tokio::time::sleep(ROUTE_HUD_DURATION), move |_| {
+                Message::CloseRouteHud(id)
+            }),
+        ])
+    }
+
+    fn rule_offer_text(&self) -> Option<String> {
+        let (host, streak) = self.rule_offer.as_ref()?;
+        let target = self.target_label(&streak.bundle_id, &streak.profile);
+        Some(format!(
+            "You opened {} in {} {} times in a row. Always open {} there?",
+            host,
+            target,
+            streak.count,
+            self.settings.auto_rule_scope.match_value(host)
+        ))
+    }
+
+    fn open_current_url(
+        &self,
+        bundle_id: &str,
+        profile: Option<String>,
+        private: bool,
+        matched: Option<&MatchItem>,
+    ) -> Option<(String, String)> {
+        let url = self.current_url.clone()?;
+        let path = self.open_with(&url, bundle_id, profile.clone(), private)?;
+        self.record_history(
+            url.clone(),
+            bundle_id.to_string(),
+            profile,
+            matched,
+            self.url_source.clone(),
+        );
+        Some((url, path))
+    }
+
+    fn open_with(
+        &self,
+        url: &str,
+        bundle_id: &str,
+        profile: Option<String>,
+        private: bool,
+    ) -> Option<String> {
+        let action = actions::find(bundle_id, &self.actions());
+        let path = if action.is_some() {
+            String::new()
+        } else if let Some(path) = self.resolve_browser_path(bundle_id) {
+            path
+        } else {
+            warn!("unable to resolve application for {}", bundle_id);
+            return None;
+        };
+        let launch_command = self
+            .storage
+            .get_browser(bundle_id.to_string())
+            .and_then(|browser| browser.launch_command);
+        match &action {
+            Some(action) => {
+                if let Err(err) = action.perform(url) {
+                    warn!("{} failed: {}", action.name(), err);
+                }
+            }
+            None => open_url(
+                url.to_string(),
+                path.clone(),
+                bundle_id.to_string(),
+                profile,
+                private,
+                launch_command,
+            ),
+        }
+        Some(path)
+    }
+
+    fn resolve_browser_path(&self, bundle_id: &str) -> Option<String> {
+        self.storage
+            .get_browser(bundle_id.to_string())
+            .map(|browser| browser.path)
+            .or_else(|| platform_tools::get_app_path(bundle_id))
+            .or_else(|| {
+                (bundle_id.starts_with('/') && std::path::Path::new(bundle_id).exists())
+                    .then(|| bundle_id.to_string())
+            })
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        let reading_list_path = self.settings.reading_list_path.trim();
+        actions::available(
+            (!reading_list_path.is_empty())
+                .then(|| platform_tools::app_dirs::expand_home(reading_list_path)),
+            &self.settings.script_command,
+        )
+    }
+
+    fn can_launch(&self, bundle_id: &str, url: &str) -> bool {
+        if actions::is_action(bundle_id) {
+            return actions::find(bundle_id, &self.actions()).is_some();
+        }
+        app_links::can_open(bundle_id, url) && self.resolve_browser_path(bundle_id).is_some()
+    }
+
     fn view(&self, window_id: window::Id) -> Element<Message> {
         if self.menu_window.is_some() && self.menu_window.as_ref().unwrap().window_id == window_id {
             let MenuWindow {
-                browser_list,
+                filter,
+                selected,
+                save_scope,
+                url_editor,
                 is_default_browser,
                 current_page,
                 ..
@@ -454,27 +1810,52 @@ impl Gomi {
                     if !is_default_browser {
                         pages::set_default_browser::set_default_browser(Message::SetAsDefault)
                     } else {
-                        let shift_key_pressed = self.keyboard.shift();
-                        let logo_key_pressed = self.keyboard.logo();
-                        pages::select_browser::select_browser(
-                            browser_list,
-                            |path| {
-                                if shift_key_pressed {
-                                    Message::ShowMatchContainEditor(path.clone(), None)
-                                } else {
-                                    Message::LaunchBrowser(
-                                        path,
-                                        None,
-                                        if logo_key_pressed {
-                                            Some(ExternalOperation::SaveEqual)
-                                        } else {
-                                            None
-                                        },
-                                    )
-                                }
-                            },
+                        let entries = self.picker_entries();
+                        let select_browser = pages::select_browser::select_browser(
+                            &entries,
+                            filter,
+                            *selected,
+                            self.suggested_target(&entries),
+                            |bundle_id| self.launch_message(bundle_id, None),
+                            |bundle_id, profile| self.launch_message(bundle_id, Some(profile)),
                             |browser| Message::ListProfiles(browser),
-                        )
+                        );
+                        let mut column = Column::new();
+                        if let Some(reviewed) = &self.reviewed_rule {
+                            column = column.push(pages::components::notice_bar::action_bar(
+                                self.review_text(reviewed),
+                                vec![
+                                    ("Edit rule", Message::EditReviewedRule),
+                                    ("Delete rule", Message::DeleteReviewedRule),
+                                ],
+                            ));
+                        } else if let Some(offer) = self.rule_offer_text() {
+                            column = column.push(pages::components::notice_bar::offer_bar(
+                                offer,
+                                "Create rule",
+                                Message::AcceptRuleOffer,
+                                Message::DismissRuleOffer,
+                            ));
+                        }
+                        if self.edited_url_invalid() {
+                            column = column.push(pages::components::notice_bar::action_bar(
+                                "The edited URL isn't valid; fix it to open it".to_string(),
+                                vec![("Reset", Message::ResetEditedUrl)],
+                            ));
+                        }
+                        if let Some(notice) = self.private_unsupported_text() {
+                            column = column
+                                .push(pages::components::notice_bar::action_bar(notice, vec![]));
+                        }
+                        if let Some(url) = &self.current_url {
+                            column = column.push(pages::components::save_panel::save_panel(
+                                &SaveScope::available(url),
+                                *save_scope,
+                                save_scope.map(|scope| scope.preview(url)),
+                                Message::SelectSaveScope,
+                            ));
+                        }
+                        container(column.push(select_browser))
                     }
                 }
                 MenuWindowPage::ProfileSelector {
@@ -482,26 +1863,11 @@ impl Gomi {
                     browser,
                     profile_text,
                 } => {
-                    let shift_key_pressed = self.keyboard.shift();
-                    let logo_key_pressed = self.keyboard.logo();
-                    let path = browser.path.clone();
+                    let bundle_id = browser.bundle_id.clone();
                     pages::select_profile::select_profile(
                         profiles,
-                        |profile| {
-                            if shift_key_pressed {
-                                Message::ShowMatchContainEditor(path.clone(), Some(profile.clone()))
-                            } else {
-                                Message::LaunchBrowser(
-                                    path.clone(),
-                                    Some(profile.clone()),
-                                    if logo_key_pressed {
-                                        Some(ExternalOperation::SaveEqual)
-                                    } else {
-                                        None
-                                    },
-                                )
-                            }
-                        },
+                        *selected,
+                        |profile| self.launch_message(bundle_id.clone(), Some(profile)),
                         |profile| Message::DeleteProfile(profile),
                         Message::Back,
                         |text| Message::TypeProfileText(text),
@@ -511,32 +1877,179 @@ impl Gomi {
                 }
                 MenuWindowPage::MatchContainEditor {
                     match_container_text,
-                    browser_path,
+                    bundle_id,
                     profile,
+                    private,
+                    preview,
+                    ..
                 } => pages::edit_match_value::edit_match_value(
                     Message::Back,
                     Message::LaunchBrowser(
-                        browser_path.clone(),
+                        bundle_id.clone(),
                         profile.clone(),
+                        *private,
                         Some(ExternalOperation::SaveContain),
                     ),
                     Message::TypeMatchContainText,
                     match_container_text,
+                    preview,
                 ),
             };
-            let footer = footer(self.current_url.clone(), Message::RefreshBrowserList);
+            let footer = footer(
+                self.current_url.clone(),
+                url_editor
+                    .as_ref()
+                    .map(|url_editor| url_editor.text.as_str()),
+                Message::ToggleUrlEditor,
+                Message::EditUrl,
+                Message::ResetEditedUrl,
+                Message::CopyUrl,
+                Message::RefreshBrowserList,
+            );
             Column::new().push(content).push(footer).into()
         } else if self.setting_window.is_some()
             && window_id == self.setting_window.as_ref().unwrap().window_id
         {
-            let match_items = self.setting_window.as_ref().unwrap().match_items.clone();
-            let browser_list = self.setting_window.as_ref().unwrap().browser_list.clone();
-            let content = pages::rule_manager::rule_manager(
-                match_items,
-                browser_list,
-                Message::DeleteMatchItem,
+            let setting_window = self.setting_window.as_ref().unwrap();
+            let tabs = pages::components::tab_bar::tab_bar(
+                &[
+                    (SettingTab::Rules, "Rules"),
+                    (SettingTab::Browsers, "Browsers"),
+                    (SettingTab::History, "History"),
+                    (SettingTab::Preferences, "Preferences"),
+                ],
+                setting_window.current_tab,
+                Message::SwitchSettingTab,
             );
-            Column::new().push(content).into()
+            let content = match setting_window.current_tab {
+                SettingTab::Rules => pages::rule_manager::rule_manager(
+                    setting_window.match_items.clone(),
+                    setting_window.browser_list.clone(),
+                    setting_window
+                        .orphaned_match_items
+                        .iter()
+                        .map(|item| item.match_value.clone())
+                        .collect(),
+                    Message::DeleteMatchItem,
+                    Message::ToggleMatchPrivate,
+                ),
+                SettingTab::Browsers => pages::browser_manager::browser_manager(
+                    setting_window.browser_list.clone(),
+                    setting_window.manual_bundle_ids.clone(),
+                    self.settings.search_paths.clone(),
+                    &setting_window.search_path_text,
+                    &setting_window.manual_browser_form,
+                    setting_window.editing_browser_name.as_ref(),
+                    Message::TypeSearchPathText,
+                    Message::AddSearchPath,
+                    Message::RemoveSearchPath,
+                    Message::TypeManualAppPath,
+                    Message::AddManualApp,
+                    Message::TypeCustomBrowserName,
+                    Message::TypeCustomCommand,
+                    Message::AddCustomCommand,
+                    Message::RemoveManualBrowser,
+                    Message::ToggleBrowserHidden,
+                    Message::TypeBrowserName,
+                    Message::RenameBrowser,
+                    Message::MoveBrowser,
+                ),
+                SettingTab::History => pages::history::history(
+                    &setting_window.history_entries,
+                    &setting_window.rule_targets,
+                    &setting_window.history_query,
+                    &setting_window.history_browser,
+                    setting_window.history_range,
+                    Message::SearchHistory,
+                    Message::FilterHistoryBrowser,
+                    Message::FilterHistoryRange,
+                    Message::ReopenHistoryEntry,
+                    Message::HistoryEntryToRule,
+                ),
+                SettingTab::Preferences => pages::preferences::preferences(
+                    self.settings.private_modifier,
+                    self.settings.bypass_modifier,
+                    &self.settings.reading_list_path,
+                    &self.settings.script_command,
+                    self.settings.inline_profiles,
+                    self.settings.auto_rule_mode,
+                    self.settings.auto_rule_threshold,
+                    self.settings.auto_rule_scope,
+                    self.settings.history_retention,
+                    self.settings.url_storage,
+                    self.settings.hash_exact_rules,
+                    setting_window.confirm_rule_hashing,
+                    &self.settings.excluded_domains,
+                    &setting_window.excluded_domain_text,
+                    Message::SetPrivateModifier,
+                    Message::SetBypassModifier,
+                    Message::SetReadingListPath,
+                    Message::SetScriptCommand,
+                    Message::SetInlineProfiles,
+                    Message::ClearUsageHistory,
+                    Message::SetAutoRuleMode,
+                    Message::SetAutoRuleThreshold,
+                    Message::SetAutoRuleScope,
+                    Message::SetHistoryRetention,
+                    Message::SetUrlStorage,
+                    Message::SetHashExactRules,
+                    Message::ConfirmHashExactRules,
+                    Message::TypeExcludedDomain,
+                    Message::AddExcludedDomain,
+                    Message::RemoveExcludedDomain,
+                    Message::PurgeHistory,
+                ),
+            };
+            let mut column = Column::new().push(tabs);
+            if !self.removed_browsers.is_empty() {
+                let notices = self
+                    .removed_browsers
+                    .iter()
+                    .map(|browser| {
+                        let rule_count = setting_window
+                            .match_items
+                            .iter()
+                            .filter(|item| item.bundle_id == browser.bundle_id)
+                            .count();
+                        format!(
+                            "{} is no longer installed. {} rule{} still open links in it.",
+                            browser.name,
+                            rule_count,
+                            if rule_count == 1 { "" } else { "s" }
+                        )
+                    })
+                    .collect();
+                column = column.push(pages::components::notice_bar::notice_bar(
+                    notices,
+                    Message::DismissRemovedBrowsers,
+                ));
+            }
+            if setting_window.current_tab == SettingTab::Rules
+                && (!setting_window.orphaned_match_items.is_empty()
+                    || !setting_window.orphaned_profiles.is_empty())
+            {
+                column = column.push(pages::rule_manager::orphan_repair(
+                    setting_window.orphaned_match_items.len(),
+                    setting_window.orphaned_profiles.len(),
+                    setting_window.rule_targets.clone(),
+                    setting_window.rule_target.clone(),
+                    Message::SelectRuleTarget,
+                    Message::ReassignOrphanedRules,
+                    Message::DeleteOrphanedRules,
+                    Message::DeleteOrphanedProfiles,
+                ));
+            }
+            column.push(content).into()
+        } else if let Some(hud) = self
+            .route_hud
+            .as_ref()
+            .filter(|hud| hud.window_id == window_id)
+        {
+            pages::route_hud::route_hud(
+                format!("{} — change?", self.routed_text(&hud.rule)),
+                Message::ChangeRoutedUrl,
+            )
+            .into()
         } else {
             Column::new().push(text("No window")).into()
         }
@@ -554,11 +2067,20 @@ impl Gomi {
             } else {
                 iced::time::every(Duration::from_secs(1)).map(|_| Message::CheckDefaultStatus)
             },
-            event::listen_with(|event, _status, window| -> Option<Message> {
+            {
+                let dirs = platform_tools::app_dirs::search_dirs(&self.settings.search_paths);
+                Subscription::run_with_id(dirs.clone(), app_dirs_subscription(dirs))
+            },
+            event::listen_with(|event, status, window| -> Option<Message> {
                 match event {
                     Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
                         Some(Message::KeyboardModifiersChanged(modifiers))
                     }
+                    Event::Keyboard(keyboard::Event::KeyPressed { key, .. })
+                        if status == event::Status::Ignored =>
+                    {
+                        Some(Message::KeyPressed(window, key))
+                    }
                     Event::Window(window::Event::Unfocused) => {
                         if IS_DEBUG {
                             None
@@ -575,6 +2097,13 @@ impl Gomi {
     }
 }
 
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 fn main() -> iced::Result {
     let file_appender = tracing_appender::rolling::daily(LOG_DIR, LOG_FILE);
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
@@ -1,10 +1,24 @@
-use iced::{keyboard::Modifiers, widget::text_editor, window};
+use iced::{
+    keyboard::{Key, Modifiers},
+    widget::text_editor,
+    window,
+};
+use std::fmt;
 
-use crate::storage::BrowserInfo;
+use crate::{
+    save_scope::SaveScope,
+    settings::{AutoRuleMode, BypassModifier, ModifierKey, Retention, RuleScope},
+    storage::{BrowserInfo, HistoryEntry, MatchItem, UrlStorage},
+};
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    LaunchBrowser(String, Option<String>, Option<ExternalOperation>),
+    LaunchBrowser(String, Option<String>, bool, Option<ExternalOperation>),
+    RouteUrl(MatchItem),
+    CloseRouteHud(window::Id),
+    ChangeRoutedUrl,
+    EditReviewedRule,
+    DeleteReviewedRule,
     SetAsDefault,
     ReceiveUrl(String),
     CheckDefaultStatus,
@@ -13,22 +27,73 @@ pub enum Message {
     Back,
     AddProfile,
     TypeProfileText(String),
-    ShowMatchContainEditor(String, Option<String>),
+    ShowMatchContainEditor(String, Option<String>, bool),
+    SelectSaveScope(SaveScope),
+    ToggleUrlEditor,
+    EditUrl(String),
+    ResetEditedUrl,
+    CopyUrl,
     TypeMatchContainText(text_editor::Action),
     KeyboardModifiersChanged(Modifiers),
+    KeyPressed(window::Id, Key),
     OpenWindow(WindowType),
     CloseWindow(WindowType),
     MoveWindow(window::Id),
     WindowClosed(window::Id),
     WindowUnfocused(window::Id),
     DeleteMatchItem(String),
+    ToggleMatchPrivate(String, bool),
+    SwitchSettingTab(SettingTab),
+    SetPrivateModifier(ModifierKey),
+    SetBypassModifier(BypassModifier),
+    SetReadingListPath(String),
+    SetScriptCommand(String),
+    SetInlineProfiles(bool),
+    ClearUsageHistory,
+    SetAutoRuleMode(AutoRuleMode),
+    SetAutoRuleThreshold(u32),
+    SetAutoRuleScope(RuleScope),
+    SetHistoryRetention(Retention),
+    SetUrlStorage(UrlStorage),
+    SetHashExactRules(bool),
+    ConfirmHashExactRules,
+    TypeExcludedDomain(String),
+    AddExcludedDomain,
+    RemoveExcludedDomain(String),
+    PurgeHistory,
+    AcceptRuleOffer,
+    DismissRuleOffer,
+    TypeSearchPathText(String),
+    AddSearchPath,
+    RemoveSearchPath(String),
+    TypeManualAppPath(String),
+    AddManualApp,
+    TypeCustomBrowserName(String),
+    TypeCustomCommand(String),
+    AddCustomCommand,
+    RemoveManualBrowser(String),
+    ToggleBrowserHidden(String, bool),
+    TypeBrowserName(String, String),
+    RenameBrowser,
+    MoveBrowser(String, isize),
     RefreshBrowserList,
+    CheckInstalledApps,
+    DismissRemovedBrowsers,
+    SelectRuleTarget(RuleTarget),
+    ReassignOrphanedRules,
+    DeleteOrphanedRules,
+    DeleteOrphanedProfiles,
+    SearchHistory(String),
+    FilterHistoryBrowser(HistoryBrowser),
+    FilterHistoryRange(HistoryRange),
+    ReopenHistoryEntry(HistoryEntry, RuleTarget),
+    HistoryEntryToRule(HistoryEntry),
     CloseApplication,
 }
 
 #[derive(Debug, Clone)]
 pub enum ExternalOperation {
-    SaveEqual,
+    SaveRule(SaveScope),
     SaveContain,
 }
 
@@ -37,3 +102,90 @@ pub enum WindowType {
     Menu,
     Setting,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingTab {
+    Rules,
+    Browsers,
+    History,
+    Preferences,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleTarget {
+    pub bundle_id: String,
+    pub browser_path: String,
+    pub name: String,
+    pub profile: Option<String>,
+    pub profile_name: Option<String>,
+}
+
+impl fmt::Display for RuleTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.profile_name {
+            Some(profile_name) => write!(f, "{} · {}", self.name, profile_name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryBrowser {
+    pub bundle_id: Option<String>,
+    pub name: String,
+}
+
+impl Default for HistoryBrowser {
+    fn default() -> Self {
+        HistoryBrowser {
+            bundle_id: None,
+            name: "All browsers".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for HistoryBrowser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryRange {
+    #[default]
+    AnyTime,
+    Day,
+    Week,
+    Month,
+}
+
+impl HistoryRange {
+    pub const ALL: [HistoryRange; 4] = [
+        HistoryRange::AnyTime,
+        HistoryRange::Day,
+        HistoryRange::Week,
+        HistoryRange::Month,
+    ];
+
+    pub fn since(&self, now: u64) -> Option<u64> {
+        let days = match self {
+            HistoryRange::AnyTime => return None,
+            HistoryRange::Day => 1,
+            HistoryRange::Week => 7,
+            HistoryRange::Month => 30,
+        };
+        Some(now.saturating_sub(days * 24 * 60 * 60))
+    }
+}
+
+impl fmt::Display for HistoryRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            HistoryRange::AnyTime => "Any time",
+            HistoryRange::Day => "Last 24 hours",
+            HistoryRange::Week => "Last 7 days",
+            HistoryRange::Month => "Last 30 days",
+        };
+        write!(f, "{}", label)
+    }
+}
@@ -0,0 +1,284 @@
+use iced::{
+    widget::{
+        checkbox, container,
+        image::{self},
+        row, text, text_input, Column, Container, Row, Text,
+    },
+    Alignment, Background, Border, Color, Length, Padding, Theme,
+};
+
+use super::components::icon_button::{icon_button, ICON};
+use super::components::scroll_view;
+use crate::platform_tools::app_dirs::display_location;
+use crate::storage::BrowserInfo;
+
+const SPACING: f32 = 10.0;
+const APP_WIDTH: f32 = 80.0;
+const NAME_WIDTH: f32 = 200.0;
+const BUNDLE_ID_WIDTH: f32 = 240.0;
+const SHOW_WIDTH: f32 = 40.0;
+const ACTION_WIDTH: f32 = 80.0;
+
+#[derive(Debug, Default)]
+pub struct ManualBrowserForm {
+    pub app_path: String,
+    pub name: String,
+    pub command: String,
+    pub error: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn browser_manager<'a, Message: 'a + Clone>(
+    browser_list: Vec<BrowserInfo>,
+    manual_bundle_ids: Vec<String>,
+    search_paths: Vec<String>,
+    search_path_text: &str,
+    form: &ManualBrowserForm,
+    editing_name: Option<&(String, String)>,
+    type_search_path_text: impl Fn(String) -> Message + 'a,
+    add_search_path: Message,
+    remove_search_path: impl Fn(String) -> Message,
+    type_app_path: impl Fn(String) -> Message + 'a,
+    add_app: Message,
+    type_command_name: impl Fn(String) -> Message + 'a,
+    type_command: impl Fn(String) -> Message + 'a,
+    add_command: Message,
+    remove_manual_browser: impl Fn(String) -> Message,
+    toggle_hidden: impl Fn(String, bool) -> Message,
+    type_browser_name: impl Fn(String, String) -> Message + 'a,
+    rename_browser: Message,
+    move_browser: impl Fn(String, isize) -> Message,
+) -> Container<'a, Message> {
+    let mut content = Column::new().spacing(5).padding(15).width(Length::Fill);
+
+    content = content.push(
+        Text::new("Custom search paths")
+            .size(12)
+            .style(header_text_style()),
+    );
+    for search_path in search_paths {
+        content = content.push(
+            container(
+                Row::new()
+                    .spacing(SPACING)
+                    .align_y(Alignment::Center)
+                    .push(
+                        Text::new(search_path.clone())
+                            .size(11)
+                            .width(Length::Fill)
+                            .style(cell_text_style()),
+                    )
+                    .push(icon_button(
+                        ICON::Remove,
+                        remove_search_path(search_path),
+                        "Remove search path".to_string(),
+                    )),
+            )
+            .style(row_style())
+            .padding(8),
+        );
+    }
+    content = content.push(
+        row![
+            text_input("/path/to/Applications or ~/Tools", search_path_text)
+                .on_input(type_search_path_text)
+                .on_submit(add_search_path.clone())
+                .size(12)
+                .width(Length::Fill),
+            icon_button(ICON::Add, add_search_path, "Add search path".to_string())
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center)
+        .height(30.0),
+    );
+
+    content = content.push(
+        Text::new("Add a browser manually")
+            .size(12)
+            .style(header_text_style()),
+    );
+    content = content.push(
+        row![
+            text_input("/path/to/Browser.app", &form.app_path)
+                .on_input(type_app_path)
+                .on_submit(add_app.clone())
+                .size(12)
+                .width(Length::Fill),
+            icon_button(ICON::Add, add_app, "Add app".to_string())
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center)
+        .height(30.0),
+    );
+    content = content.push(
+        row![
+            text_input("Name", &form.name)
+                .on_input(type_command_name)
+                .size(12)
+                .width(Length::Fixed(NAME_WIDTH)),
+            text_input(
+                "/usr/local/bin/vm-open --profile {profile} {url}",
+                &form.command
+            )
+            .on_input(type_command)
+            .on_submit(add_command.clone())
+            .size(12)
+            .width(Length::Fill),
+            icon_button(ICON::Add, add_command, "Add command".to_string())
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center)
+        .height(30.0),
+    );
+    if let Some(error) = &form.error {
+        content = content.push(Text::new(error.clone()).size(11).style(|_| text::Style {
+            color: Some(Color::from_rgb(0.8, 0.3, 0.2)),
+        }));
+    }
+
+    let header = Row::new()
+        .spacing(SPACING)
+        .height(Length::Fixed(30.0))
+        .align_y(Alignment::Center)
+        .push(
+            container(Text::new("App").size(12).style(header_text_style()))
+                .width(Length::Fixed(APP_WIDTH))
+                .center_y(Length::Fill),
+        )
+        .push(
+            container(Text::new("Name").size(12).style(header_text_style()))
+                .width(Length::Fixed(NAME_WIDTH))
+                .center_y(Length::Fill),
+        )
+        .push(
+            container(Text::new("Bundle ID").size(12).style(header_text_style()))
+                .width(Length::Fixed(BUNDLE_ID_WIDTH))
+                .center_y(Length::Fill),
+        )
+        .push(
+            container(Text::new("Found In").size(12).style(header_text_style()))
+                .width(Length::Fill)
+                .center_y(Length::Fill),
+        )
+        .push(
+            container(Text::new("Show").size(12).style(header_text_style()))
+                .width(Length::Fixed(SHOW_WIDTH))
+                .center_y(Length::Fill),
+        )
+        .push(container(Text::new("")).width(Length::Fixed(ACTION_WIDTH)));
+    content = content.push(
+        container(header)
+            .style(|_| container::Style {
+                background: Some(Background::Color(Color::from_rgb(0.95, 0.95, 0.95))),
+                ..Default::default()
+            })
+            .padding(Padding::new(8.0).top(0.0).bottom(0.0)),
+    );
+
+    let type_browser_name = std::rc::Rc::new(type_browser_name);
+    for browser in browser_list {
+        let location = match &browser.launch_command {
+            Some(launch_command) => launch_command.clone(),
+            None => display_location(&browser.path),
+        };
+        let name = match editing_name {
+            Some((bundle_id, text)) if *bundle_id == browser.bundle_id => text.clone(),
+            _ => browser.name.clone(),
+        };
+        let mut action = Row::new()
+            .spacing(5)
+            .push(icon_button(
+                ICON::Up,
+                move_browser(browser.bundle_id.clone(), -1),
+                "Move up".to_string(),
+            ))
+            .push(icon_button(
+                ICON::Down,
+                move_browser(browser.bundle_id.clone(), 1),
+                "Move down".to_string(),
+            ));
+        if manual_bundle_ids.contains(&browser.bundle_id) {
+            action = action.push(icon_button(
+                ICON::Remove,
+                remove_manual_browser(browser.bundle_id.clone()),
+                "Remove browser".to_string(),
+            ));
+        }
+        let toggle = toggle_hidden(browser.bundle_id.clone(), !browser.hidden);
+        let type_name = type_browser_name.clone();
+        let bundle_id = browser.bundle_id.clone();
+        let row = Row::new()
+            .spacing(SPACING)
+            .align_y(Alignment::Center)
+            .push(
+                container(
+                    image::viewer(image::Handle::from_bytes(browser.icon_data.clone()))
+                        .width(Length::Fixed(16.0))
+                        .height(Length::Fixed(16.0)),
+                )
+                .padding(Padding::new(0.0).left(5.0))
+                .width(Length::Fixed(APP_WIDTH)),
+            )
+            .push(
+                text_input("Name", &name)
+                    .on_input(move |text| type_name(bundle_id.clone(), text))
+                    .on_submit(rename_browser.clone())
+                    .size(11)
+                    .width(Length::Fixed(NAME_WIDTH)),
+            )
+            .push(
+                Text::new(browser.bundle_id.clone())
+                    .size(11)
+                    .width(Length::Fixed(BUNDLE_ID_WIDTH))
+                    .style(cell_text_style()),
+            )
+            .push(
+                Text::new(location)
+                    .size(11)
+                    .width(Length::Fill)
+                    .style(cell_text_style()),
+            )
+            .push(
+                container(
+                    checkbox("", !browser.hidden)
+                        .size(14)
+                        .on_toggle(move |_| toggle.clone()),
+                )
+                .width(Length::Fixed(SHOW_WIDTH)),
+            )
+            .push(container(action).width(Length::Fixed(ACTION_WIDTH)));
+        content = content.push(container(row).style(row_style()).padding(8));
+    }
+
+    container(scroll_view::scroll_view(content))
+        .style(|_| container::Style {
+            background: Some(Background::Color(Color::from_rgb(0.98, 0.98, 0.98))),
+            ..Default::default()
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+}
+
+fn row_style() -> impl Fn(&Theme) -> container::Style {
+    |_| container::Style {
+        background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+        border: Border {
+            radius: 4.0.into(),
+            width: 1.0,
+            color: Color::from_rgb(0.95, 0.95, 0.95),
+        },
+        ..Default::default()
+    }
+}
+
+fn cell_text_style() -> impl Fn(&Theme) -> text::Style {
+    |_| text::Style {
+        color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
+    }
+}
+
+fn header_text_style() -> impl Fn(&Theme) -> text::Style {
+    |_| text::Style {
+        color: Some(Color::from_rgb(0.4, 0.4, 0.4)),
+    }
+}
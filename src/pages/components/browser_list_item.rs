@@ -13,9 +13,9 @@ pub fn browser_list_item<'a, Message: 'a + Clone>(
     start_icon: Option<Viewer<image::Handle>>,
     name: String,
     on_press: Message,
-    end_icon: ICON,
-    end_on_press: Message,
-    end_tip: String,
+    end_button: Option<(ICON, Message, String)>,
+    badge: Option<String>,
+    selected: bool,
 ) -> Button<'a, Message> {
     let mut content = row![]
         .spacing(12)
@@ -35,16 +35,44 @@ pub fn browser_list_item<'a, Message: 'a + Clone>(
             .width(Length::Fill),
     );
 
-    content = content.push(icon_button(end_icon, end_on_press, end_tip));
+    if let Some(badge) = badge {
+        content = content.push(
+            container(Text::new(badge).size(10).style(|_| text::Style {
+                color: Some(Color::from_rgb(0.3, 0.4, 0.7)),
+            }))
+            .padding([2, 6])
+            .style(|_| container::Style {
+                background: Some(Background::Color(Color::from_rgb(0.93, 0.95, 1.0))),
+                border: Border {
+                    radius: 4.0.into(),
+                    width: 1.0,
+                    color: Color::from_rgb(0.8, 0.85, 1.0),
+                },
+                ..Default::default()
+            }),
+        );
+    }
+
+    if let Some((end_icon, end_on_press, end_tip)) = end_button {
+        content = content.push(icon_button(end_icon, end_on_press, end_tip));
+    }
 
     button(content)
         .on_press(on_press)
-        .style(|_, _| button::Style {
-            background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+        .style(move |_, _| button::Style {
+            background: Some(Background::Color(if selected {
+                Color::from_rgb(0.95, 0.95, 1.0)
+            } else {
+                Color::from_rgb(1.0, 1.0, 1.0)
+            })),
             border: Border {
                 radius: 8.0.into(),
                 width: 1.0,
-                color: Color::from_rgb(0.9, 0.9, 0.9),
+                color: if selected {
+                    Color::from_rgb(0.7, 0.7, 0.9)
+                } else {
+                    Color::from_rgb(0.9, 0.9, 0.9)
+                },
             },
             text_color: Color::from_rgb(0.2, 0.2, 0.2),
             shadow: Shadow {
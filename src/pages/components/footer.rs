@@ -1,5 +1,5 @@
 use iced::{
-    widget::{center, container, row, text, tooltip, Container, Text},
+    widget::{button, center, container, row, text, text_input, tooltip, Button, Container, Text},
     Alignment, Background, Border, Color, Length,
 };
 use url::Url;
@@ -8,19 +8,51 @@ use crate::pages::components::icon_button::{icon_button, ICON};
 
 pub fn footer<'a, Message: Clone + 'a>(
     url: Option<String>,
+    edited_url: Option<&str>,
+    on_toggle_edit: Message,
+    on_edit: impl Fn(String) -> Message + 'a,
+    on_reset: Message,
+    on_copy: Message,
     on_refresh: Message,
 ) -> Container<'a, Message> {
-    let url_view = if let Some(url) = url {
+    let url_view = if let Some(edited_url) = edited_url {
+        let valid = Url::parse(edited_url).is_ok();
+        container(
+            row![
+                text_input("URL", edited_url)
+                    .on_input(on_edit)
+                    .size(12)
+                    .padding([4, 6])
+                    .style(move |theme, status| {
+                        let mut style = text_input::default(theme, status);
+                        if !valid {
+                            style.border.color = Color::from_rgb(0.9, 0.3, 0.3);
+                        }
+                        style
+                    }),
+                small_button("Copy", on_copy),
+                small_button("Reset", on_reset),
+                small_button("Done", on_toggle_edit),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center),
+        )
+        .padding([4, 6])
+        .width(Length::Fill)
+    } else if let Some(url) = url {
         let url_cloned = url.clone();
         let url = Url::parse(&url).unwrap();
         let host = url.host_str().unwrap_or_default().to_string();
 
         container(tooltip(
-            Text::new(host).size(13).style(|_| text::Style {
+            button(Text::new(host).size(13).style(|_| text::Style {
                 color: Some(Color::from_rgb(0.2, 0.2, 0.2)),
-            }),
+            }))
+            .padding(0)
+            .style(|_, _| button::Style::default())
+            .on_press(on_toggle_edit),
             container(
-                Text::new(url_cloned)
+                Text::new(url_cloned + "\nClick to edit")
                     .size(13)
                     .color(Color::from_rgb(0.2, 0.2, 0.2)),
             )
@@ -63,3 +95,18 @@ pub fn footer<'a, Message: Clone + 'a>(
         .align_y(Alignment::Center);
     container(row).width(Length::Fill)
 }
+
+fn small_button<'a, Message: Clone + 'a>(label: &str, on_press: Message) -> Button<'a, Message> {
+    button(Text::new(label.to_string()).size(11))
+        .style(|_, _| button::Style {
+            background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+            border: Border {
+                radius: 4.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.9, 0.9, 0.9),
+            },
+            ..button::Style::default()
+        })
+        .padding([3, 6])
+        .on_press(on_press)
+}
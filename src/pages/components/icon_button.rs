@@ -3,7 +3,9 @@ use iced::{
     Background, Border, Color, Shadow,
 };
 
-use super::icons::{add_icon, back_icon, profile_icon, refresh_icon, remove_icon};
+use super::icons::{
+    add_icon, back_icon, down_icon, profile_icon, refresh_icon, remove_icon, up_icon,
+};
 
 pub enum ICON {
     Profile,
@@ -11,6 +13,8 @@ pub enum ICON {
     Remove,
     Back,
     Refresh,
+    Up,
+    Down,
 }
 
 pub fn icon_button<'a, Message: Clone + 'a>(
@@ -25,6 +29,8 @@ pub fn icon_button<'a, Message: Clone + 'a>(
             ICON::Remove => remove_icon(),
             ICON::Back => back_icon(),
             ICON::Refresh => refresh_icon(),
+            ICON::Up => up_icon(),
+            ICON::Down => down_icon(),
         }),
         container(
             Text::new(tip)
@@ -24,3 +24,11 @@ pub fn back_icon<'a, Message>() -> Element<'a, Message> {
 pub fn refresh_icon<'a, Message>() -> Element<'a, Message> {
     icon('\u{0e796}')
 }
+
+pub fn up_icon<'a, Message>() -> Element<'a, Message> {
+    icon('\u{0e702}')
+}
+
+pub fn down_icon<'a, Message>() -> Element<'a, Message> {
+    icon('\u{0e703}')
+}
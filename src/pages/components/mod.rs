@@ -1,5 +1,8 @@
+pub mod browser_list_item;
 pub mod footer;
 pub mod icon_button;
 pub mod icons;
-pub mod browser_list_item;
+pub mod notice_bar;
+pub mod save_panel;
 pub mod scroll_view;
+pub mod tab_bar;
@@ -0,0 +1,83 @@
+use iced::{
+    widget::{button, container, text, Button, Column, Container, Row, Text},
+    Alignment, Background, Border, Color, Length, Theme,
+};
+
+pub fn notice_bar<'a, Message: Clone + 'a>(
+    lines: Vec<String>,
+    on_dismiss: Message,
+) -> Container<'a, Message> {
+    let lines = lines
+        .into_iter()
+        .fold(Column::new().spacing(4), |column, line| {
+            column.push(Text::new(line).size(12).style(|_| text::Style {
+                color: Some(Color::from_rgb(0.55, 0.25, 0.0)),
+            }))
+        });
+    container(
+        Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(lines.width(Length::Fill))
+            .push(bar_button("Dismiss", on_dismiss)),
+    )
+    .padding([10, 15])
+    .width(Length::Fill)
+    .style(bar_style())
+}
+
+pub fn offer_bar<'a, Message: Clone + 'a>(
+    offer: String,
+    accept_label: &str,
+    on_accept: Message,
+    on_dismiss: Message,
+) -> Container<'a, Message> {
+    action_bar(
+        offer,
+        vec![(accept_label, on_accept), ("Not now", on_dismiss)],
+    )
+}
+
+pub fn action_bar<'a, Message: Clone + 'a>(
+    message: String,
+    actions: Vec<(&str, Message)>,
+) -> Container<'a, Message> {
+    let buttons = actions
+        .into_iter()
+        .fold(Row::new().spacing(8), |row, (label, on_press)| {
+            row.push(bar_button(label, on_press))
+        });
+    container(
+        Column::new()
+            .spacing(8)
+            .push(Text::new(message).size(12).style(|_| text::Style {
+                color: Some(Color::from_rgb(0.55, 0.25, 0.0)),
+            }))
+            .push(buttons),
+    )
+    .padding([10, 15])
+    .width(Length::Fill)
+    .style(bar_style())
+}
+
+fn bar_button<'a, Message: Clone + 'a>(label: &str, on_press: Message) -> Button<'a, Message> {
+    button(Text::new(label.to_string()).size(12))
+        .style(|_, _| button::Style {
+            background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+            border: Border {
+                radius: 4.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.9, 0.8, 0.6),
+            },
+            ..button::Style::default()
+        })
+        .padding([4, 12])
+        .on_press(on_press)
+}
+
+fn bar_style() -> impl Fn(&Theme) -> container::Style {
+    |_| container::Style {
+        background: Some(Background::Color(Color::from_rgb(1.0, 0.96, 0.88))),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,72 @@
+use iced::{
+    widget::{button, container, text, Column, Container, Row, Text},
+    Alignment, Background, Border, Color, Length,
+};
+
+use crate::save_scope::SaveScope;
+
+pub fn save_panel<'a, Message: Clone + 'a>(
+    scopes: &[SaveScope],
+    selected: Option<SaveScope>,
+    preview: Option<String>,
+    on_select: impl Fn(SaveScope) -> Message,
+) -> Container<'a, Message> {
+    let chips = scopes.iter().fold(
+        Row::new().spacing(4).align_y(Alignment::Center).push(
+            Text::new("Save rule").size(11).style(|_| text::Style {
+                color: Some(Color::from_rgb(0.4, 0.4, 0.4)),
+            }),
+        ),
+        |row, scope| {
+            row.push(chip(
+                scope.to_string(),
+                Some(*scope) == selected,
+                on_select(*scope),
+            ))
+        },
+    );
+    let hint = preview.unwrap_or_else(|| "Pick a scope to remember this choice".to_string());
+    container(
+        Column::new()
+            .spacing(4)
+            .push(chips)
+            .push(Text::new(hint).size(10).style(|_| text::Style {
+                color: Some(Color::from_rgb(0.5, 0.5, 0.5)),
+            })),
+    )
+    .padding([6, 12])
+    .width(Length::Fill)
+}
+
+fn chip<'a, Message: Clone + 'a>(
+    label: String,
+    selected: bool,
+    on_press: Message,
+) -> button::Button<'a, Message> {
+    button(Text::new(label).size(11).style(move |_| text::Style {
+        color: Some(if selected {
+            Color::from_rgb(1.0, 1.0, 1.0)
+        } else {
+            Color::from_rgb(0.3, 0.3, 0.3)
+        }),
+    }))
+    .style(move |_, _| button::Style {
+        background: Some(Background::Color(if selected {
+            Color::from_rgb(0.2, 0.5, 1.0)
+        } else {
+            Color::from_rgb(1.0, 1.0, 1.0)
+        })),
+        border: Border {
+            radius: 10.0.into(),
+            width: 1.0,
+            color: if selected {
+                Color::from_rgb(0.2, 0.5, 1.0)
+            } else {
+                Color::from_rgb(0.85, 0.85, 0.85)
+            },
+        },
+        ..button::Style::default()
+    })
+    .padding([2, 8])
+    .on_press(on_press)
+}
@@ -0,0 +1,49 @@
+use iced::{
+    widget::{button, container, row, text, Container, Text},
+    Background, Border, Color, Length,
+};
+
+pub fn tab_bar<'a, Tab: Copy + PartialEq, Message: Clone + 'a>(
+    tabs: &[(Tab, &str)],
+    current_tab: Tab,
+    on_select: impl Fn(Tab) -> Message,
+) -> Container<'a, Message> {
+    let tabs = tabs.iter().fold(row![].spacing(8), |tabs, (tab, label)| {
+        let is_current = *tab == current_tab;
+        tabs.push(
+            button(
+                Text::new(label.to_string())
+                    .size(13)
+                    .style(move |_| text::Style {
+                        color: Some(if is_current {
+                            Color::from_rgb(1.0, 1.0, 1.0)
+                        } else {
+                            Color::from_rgb(0.3, 0.3, 0.3)
+                        }),
+                    }),
+            )
+            .style(move |_, _| button::Style {
+                background: Some(Background::Color(if is_current {
+                    Color::from_rgb(0.2, 0.5, 1.0)
+                } else {
+                    Color::from_rgb(1.0, 1.0, 1.0)
+                })),
+                border: Border {
+                    radius: 4.0.into(),
+                    width: 1.0,
+                    color: Color::from_rgb(0.9, 0.9, 0.9),
+                },
+                ..button::Style::default()
+            })
+            .padding([6, 16])
+            .on_press(on_select(*tab)),
+        )
+    });
+    container(tabs)
+        .padding([10, 15])
+        .width(Length::Fill)
+        .style(|_| container::Style {
+            background: Some(Background::Color(Color::from_rgb(0.95, 0.95, 0.95))),
+            ..Default::default()
+        })
+}
@@ -2,20 +2,29 @@ use iced::widget::text_editor;
 use iced::Renderer;
 use iced::{
     widget::{
-        button, container,
-        row, text,
-        text_editor::Action, Column, Container, Text,
+        button, container, row, scrollable, text, text_editor::Action, Column, Container, Text,
     },
     Alignment, Background, Color, Length,
 };
 
 use super::components::icon_button;
+use super::rule_manager::truncate_string;
+
+#[derive(Debug, Clone, Default)]
+pub struct MatchPreview {
+    pub matches_current_url: bool,
+    pub history_count: u32,
+    pub history_urls: Vec<String>,
+    pub overrides: Vec<String>,
+    pub overridden_by: Vec<String>,
+}
 
 pub fn edit_match_value<'a, Message: 'a + Clone>(
     back: Message,
     save_and_open: Message,
     type_match_contain_text: impl Fn(Action) -> Message + 'a,
     match_container_text: &'a text_editor::Content<Renderer>,
+    preview: &MatchPreview,
 ) -> Container<'a, Message> {
     let mut content = Column::new()
         .spacing(20)
@@ -42,6 +51,55 @@ pub fn edit_match_value<'a, Message: 'a + Clone>(
     .spacing(12);
     content = content
         .push(text_editor(&match_container_text).on_action(type_match_contain_text))
+        .push(scrollable(match_preview(preview)).height(Length::Fill))
         .push(footer);
     container(content)
 }
+
+fn match_preview<'a, Message: 'a>(preview: &MatchPreview) -> Column<'a, Message> {
+    let mut column = Column::new().spacing(4).width(Length::Fill);
+    column = column.push(if preview.matches_current_url {
+        preview_line("Matches the current link", Color::from_rgb(0.2, 0.6, 0.3))
+    } else {
+        preview_line(
+            "Does not match the current link",
+            Color::from_rgb(0.8, 0.3, 0.3),
+        )
+    });
+    column = column.push(heading(match preview.history_count {
+        0 => "No past links match".to_string(),
+        1 => "1 past link matches".to_string(),
+        count => format!("{} past links match", count),
+    }));
+    column = preview.history_urls.iter().fold(column, |column, url| {
+        column.push(preview_line(
+            &truncate_string(url, 48),
+            Color::from_rgb(0.4, 0.4, 0.4),
+        ))
+    });
+    if !preview.overrides.is_empty() {
+        column = column.push(heading("Takes priority over".to_string()));
+        column = preview.overrides.iter().fold(column, |column, rule| {
+            column.push(preview_line(rule, Color::from_rgb(0.4, 0.4, 0.4)))
+        });
+    }
+    if !preview.overridden_by.is_empty() {
+        column = column.push(heading("Overridden by".to_string()));
+        column = preview.overridden_by.iter().fold(column, |column, rule| {
+            column.push(preview_line(rule, Color::from_rgb(0.8, 0.5, 0.1)))
+        });
+    }
+    column
+}
+
+fn heading<'a>(label: String) -> Text<'a> {
+    Text::new(label).size(11).style(|_| text::Style {
+        color: Some(Color::from_rgb(0.2, 0.2, 0.2)),
+    })
+}
+
+fn preview_line<'a>(label: &str, color: Color) -> Text<'a> {
+    Text::new(label.to_string())
+        .size(10)
+        .style(move |_| text::Style { color: Some(color) })
+}
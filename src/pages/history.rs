@@ -0,0 +1,243 @@
+use iced::{
+    widget::{button, container, pick_list, text, text_input, Column, Container, Row, Text},
+    Alignment, Background, Border, Color, Length,
+};
+
+use super::components::scroll_view;
+use super::rule_manager::{header_text_style, truncate_string};
+use crate::actions;
+use crate::message::{HistoryBrowser, HistoryRange, RuleTarget};
+use crate::storage::HistoryEntry;
+
+const SPACING: f32 = 10.0;
+const TIME_WIDTH: f32 = 110.0;
+const RULE_WIDTH: f32 = 90.0;
+const BROWSER_WIDTH: f32 = 120.0;
+const ACTION_WIDTH: f32 = 220.0;
+
+#[allow(clippy::too_many_arguments)]
+pub fn history<'a, Message: 'a + Clone>(
+    entries: &[HistoryEntry],
+    rule_targets: &[RuleTarget],
+    query: &str,
+    browser: &HistoryBrowser,
+    range: HistoryRange,
+    search: impl Fn(String) -> Message + 'a,
+    filter_browser: impl Fn(HistoryBrowser) -> Message + 'a,
+    filter_range: impl Fn(HistoryRange) -> Message + 'a,
+    reopen: impl Fn(HistoryEntry, RuleTarget) -> Message + Clone + 'a,
+    make_rule: impl Fn(HistoryEntry) -> Message,
+) -> Container<'a, Message> {
+    let browsers: Vec<HistoryBrowser> = std::iter::once(HistoryBrowser::default())
+        .chain(
+            rule_targets
+                .iter()
+                .filter(|target| target.profile.is_none())
+                .map(|target| HistoryBrowser {
+                    bundle_id: Some(target.bundle_id.clone()),
+                    name: target.name.clone(),
+                }),
+        )
+        .collect();
+    let filters = Row::new()
+        .spacing(SPACING)
+        .align_y(Alignment::Center)
+        .push(
+            text_input("Search history", query)
+                .on_input(search)
+                .size(12)
+                .width(Length::Fill),
+        )
+        .push(
+            pick_list(browsers, Some(browser.clone()), filter_browser)
+                .text_size(12)
+                .width(Length::Fixed(160.0)),
+        )
+        .push(
+            pick_list(HistoryRange::ALL, Some(range), filter_range)
+                .text_size(12)
+                .width(Length::Fixed(130.0)),
+        );
+
+    let header = Row::new()
+        .spacing(SPACING)
+        .height(Length::Fixed(30.0))
+        .align_y(Alignment::Center)
+        .push(header_cell("Time", Length::Fixed(TIME_WIDTH)))
+        .push(header_cell("URL", Length::Fill))
+        .push(header_cell("Rule", Length::Fixed(RULE_WIDTH)))
+        .push(header_cell("Opened In", Length::Fixed(BROWSER_WIDTH)))
+        .push(header_cell("Action", Length::Fixed(ACTION_WIDTH)));
+
+    let mut content = Column::new()
+        .spacing(5)
+        .padding(15)
+        .width(Length::Fill)
+        .push(filters)
+        .push(
+            container(header)
+                .style(|_| container::Style {
+                    background: Some(Background::Color(Color::from_rgb(0.95, 0.95, 0.95))),
+                    ..Default::default()
+                })
+                .padding(8),
+        );
+
+    if entries.is_empty() {
+        content = content.push(
+            container(
+                Text::new(if query.is_empty() {
+                    "No links opened yet"
+                } else {
+                    "No matches"
+                })
+                .size(12)
+                .style(|_| text::Style {
+                    color: Some(Color::from_rgb(0.5, 0.5, 0.5)),
+                }),
+            )
+            .padding(8),
+        );
+    }
+
+    for entry in entries {
+        let reopen = reopen.clone();
+        let reopen_entry = entry.clone();
+        let url = Column::new()
+            .spacing(2)
+            .width(Length::Fill)
+            .push(
+                Text::new(truncate_string(&entry.url, 80))
+                    .size(11)
+                    .style(|_| text::Style {
+                        color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
+                    }),
+            )
+            .push_maybe(entry.source.as_ref().map(|source| {
+                Text::new(format!("from {}", source))
+                    .size(10)
+                    .style(|_| text::Style {
+                        color: Some(Color::from_rgb(0.5, 0.5, 0.5)),
+                    })
+            }));
+        let rule = match (&entry.match_type, &entry.match_value) {
+            (Some(match_type), Some(match_value)) => format!(
+                "{} {}",
+                match_type.to_lowercase(),
+                truncate_string(match_value, 12)
+            ),
+            _ => "—".to_string(),
+        };
+        let row = Row::new()
+            .spacing(SPACING)
+            .align_y(Alignment::Center)
+            .push(cell(format_time(entry.opened_at), TIME_WIDTH))
+            .push(url)
+            .push(cell(rule, RULE_WIDTH))
+            .push(cell(target_name(rule_targets, entry), BROWSER_WIDTH))
+            .push(
+                Row::new()
+                    .spacing(6)
+                    .align_y(Alignment::Center)
+                    .width(Length::Fixed(ACTION_WIDTH))
+                    .push(
+                        pick_list(rule_targets.to_vec(), None::<RuleTarget>, move |target| {
+                            reopen(reopen_entry.clone(), target)
+                        })
+                        .placeholder("Reopen in...")
+                        .text_size(11)
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        button(Text::new("Make rule").size(11))
+                            .style(|_, _| button::Style {
+                                background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+                                border: Border {
+                                    radius: 4.0.into(),
+                                    width: 1.0,
+                                    color: Color::from_rgb(0.9, 0.9, 0.9),
+                                },
+                                ..button::Style::default()
+                            })
+                            .padding([4, 8])
+                            .on_press_maybe(
+                                entry.match_type.is_none().then(|| make_rule(entry.clone())),
+                            ),
+                    ),
+            );
+        content = content.push(
+            container(row)
+                .style(|_| container::Style {
+                    background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+                    border: Border {
+                        radius: 4.0.into(),
+                        width: 1.0,
+                        color: Color::from_rgb(0.95, 0.95, 0.95),
+                    },
+                    ..Default::default()
+                })
+                .padding(8),
+        );
+    }
+
+    container(scroll_view::scroll_view(content))
+        .style(|_| container::Style {
+            background: Some(Background::Color(Color::from_rgb(0.98, 0.98, 0.98))),
+            ..Default::default()
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+}
+
+fn header_cell<'a, Message: 'a>(label: &str, width: Length) -> Container<'a, Message> {
+    container(
+        Text::new(label.to_string())
+            .size(12)
+            .style(header_text_style()),
+    )
+    .width(width)
+    .center_y(Length::Fill)
+}
+
+fn cell<'a, Message: 'a>(value: String, width: f32) -> Container<'a, Message> {
+    container(Text::new(value).size(11).style(|_| text::Style {
+        color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
+    }))
+    .width(Length::Fixed(width))
+}
+
+fn target_name(rule_targets: &[RuleTarget], entry: &HistoryEntry) -> String {
+    let name = rule_targets
+        .iter()
+        .find(|target| target.bundle_id == entry.bundle_id && target.profile.is_none())
+        .map(|target| target.name.clone())
+        .or_else(|| actions::display_name(&entry.bundle_id).map(str::to_string))
+        .unwrap_or_else(|| entry.bundle_id.clone());
+    let Some(profile) = &entry.profile else {
+        return name;
+    };
+    let profile_name = rule_targets
+        .iter()
+        .find(|target| {
+            target.bundle_id == entry.bundle_id && target.profile.as_ref() == Some(profile)
+        })
+        .and_then(|target| target.profile_name.clone())
+        .unwrap_or_else(|| profile.clone());
+    format!("{} · {}", name, profile_name)
+}
+
+fn format_time(secs: u64) -> String {
+    let time = secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return String::new();
+    }
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min
+    )
+}
@@ -1,5 +1,9 @@
+pub mod browser_manager;
 pub mod components;
 pub mod edit_match_value;
+pub mod history;
+pub mod preferences;
+pub mod route_hud;
 pub mod rule_manager;
 pub mod select_browser;
 pub mod select_profile;
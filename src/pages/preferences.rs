@@ -0,0 +1,262 @@
+use iced::{
+    widget::{
+        button, checkbox, container, pick_list, text, text_input, Column, Container, Row, Text,
+    },
+    Alignment, Background, Border, Color, Length,
+};
+
+use super::components::icon_button::{icon_button, ICON};
+use super::components::scroll_view;
+use crate::settings::{AutoRuleMode, BypassModifier, ModifierKey, Retention, RuleScope};
+use crate::storage::UrlStorage;
+
+const AUTO_RULE_THRESHOLDS: [u32; 9] = [2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+#[allow(clippy::too_many_arguments)]
+pub fn preferences<'a, Message: 'a + Clone>(
+    private_modifier: ModifierKey,
+    bypass_modifier: BypassModifier,
+    reading_list_path: &str,
+    script_command: &str,
+    inline_profiles: bool,
+    auto_rule_mode: AutoRuleMode,
+    auto_rule_threshold: u32,
+    auto_rule_scope: RuleScope,
+    history_retention: Retention,
+    url_storage: UrlStorage,
+    hash_exact_rules: bool,
+    confirm_rule_hashing: bool,
+    excluded_domains: &[String],
+    excluded_domain_text: &str,
+    set_private_modifier: impl Fn(ModifierKey) -> Message + 'a,
+    set_bypass_modifier: impl Fn(BypassModifier) -> Message + 'a,
+    set_reading_list_path: impl Fn(String) -> Message + 'a,
+    set_script_command: impl Fn(String) -> Message + 'a,
+    set_inline_profiles: impl Fn(bool) -> Message + 'a,
+    clear_usage_history: Message,
+    set_auto_rule_mode: impl Fn(AutoRuleMode) -> Message + 'a,
+    set_auto_rule_threshold: impl Fn(u32) -> Message + 'a,
+    set_auto_rule_scope: impl Fn(RuleScope) -> Message + 'a,
+    set_history_retention: impl Fn(Retention) -> Message + 'a,
+    set_url_storage: impl Fn(UrlStorage) -> Message + 'a,
+    set_hash_exact_rules: impl Fn(bool) -> Message + 'a,
+    confirm_hash_exact_rules: Message,
+    type_excluded_domain: impl Fn(String) -> Message + 'a,
+    add_excluded_domain: Message,
+    remove_excluded_domain: impl Fn(String) -> Message,
+    purge_history: Message,
+) -> Container<'a, Message> {
+    let cancel_rule_hashing = set_hash_exact_rules(false);
+    let mut content = Column::new()
+        .spacing(5)
+        .padding(15)
+        .width(Length::Fill)
+        .push(preference_row(
+            "Private window modifier",
+            "Hold this key while choosing a browser to open the link in a private window",
+            pick_list(
+                ModifierKey::ALL
+                    .into_iter()
+                    .filter(|key| !bypass_modifier.conflicts_with(*key))
+                    .collect::<Vec<_>>(),
+                Some(private_modifier),
+                set_private_modifier,
+            )
+            .text_size(12),
+        ))
+        .push(preference_row(
+            "Skip rules modifier",
+            "Hold this key while clicking a link to always show the picker, even when a rule matches",
+            pick_list(
+                BypassModifier::ALL
+                    .into_iter()
+                    .filter(|key| !key.conflicts_with(private_modifier))
+                    .collect::<Vec<_>>(),
+                Some(bypass_modifier),
+                set_bypass_modifier,
+            )
+            .text_size(12),
+        ))
+        .push(preference_row(
+            "Show profiles in the browser list",
+            "Lists every saved browser profile as its own entry so it opens with a single click",
+            checkbox("", inline_profiles)
+                .size(14)
+                .on_toggle(set_inline_profiles),
+        ))
+        .push(preference_row(
+            "Usage history",
+            "Browsers you often pick for a site move to the top of the list; older choices count less over time",
+            small_button("Clear", clear_usage_history),
+        ))
+        .push(preference_row(
+            "Rules from repeated choices",
+            "When you pick the same browser for a site several times in a row, offer to remember it or create the rule right away",
+            pick_list(AutoRuleMode::ALL, Some(auto_rule_mode), set_auto_rule_mode).text_size(12),
+        ))
+        .push(preference_row(
+            "Repeated choices needed",
+            "How many identical choices in a row before a rule is offered or created",
+            pick_list(
+                AUTO_RULE_THRESHOLDS,
+                Some(auto_rule_threshold),
+                set_auto_rule_threshold,
+            )
+            .text_size(12),
+        ))
+        .push(preference_row(
+            "Rule scope",
+            "Match only the exact host (docs.github.com) or the whole registrable domain (github.com)",
+            pick_list(RuleScope::ALL, Some(auto_rule_scope), set_auto_rule_scope).text_size(12),
+        ))
+        .push(preference_row(
+            "Reading list file",
+            "Adds an \"Add to Reading List\" entry that appends links to this file (.md or .json)",
+            text_input("~/Documents/reading-list.md", reading_list_path)
+                .on_input(set_reading_list_path)
+                .size(12)
+                .width(Length::Fixed(260.0)),
+        ))
+        .push(preference_row(
+            "Script",
+            "Adds a \"Run Script\" entry that pipes the link into this command; {url} is replaced with the link",
+            text_input("yt-dlp {url}", script_command)
+                .on_input(set_script_command)
+                .size(12)
+                .width(Length::Fixed(260.0)),
+        ))
+        .push(preference_row(
+            "Keep history for",
+            "Recorded links and usage history older than this are deleted",
+            pick_list(
+                Retention::ALL,
+                Some(history_retention),
+                set_history_retention,
+            )
+            .text_size(12),
+        ))
+        .push(preference_row(
+            "Stored URLs",
+            "Keep full URLs in history, or only the scheme and host",
+            pick_list(UrlStorage::ALL, Some(url_storage), set_url_storage).text_size(12),
+        ))
+        .push(preference_row(
+            "Hash exact-URL rules",
+            "Stores exact-URL rules as a salted hash so they keep working without keeping the URL; rules already hashed stay hashed when this is turned off",
+            checkbox("", hash_exact_rules)
+                .size(14)
+                .on_toggle(set_hash_exact_rules),
+        ))
+        .push_maybe(confirm_rule_hashing.then(|| {
+            preference_row(
+                "Hash existing exact-URL rules?",
+                "Every saved exact-URL rule is rewritten right away. This can't be undone: the rules keep matching, but their URLs can no longer be shown",
+                Row::new()
+                    .spacing(6)
+                    .push(small_button("Cancel", cancel_rule_hashing))
+                    .push(small_button("Hash Rules", confirm_hash_exact_rules)),
+            )
+        }))
+        .push(preference_row(
+            "Don't record",
+            "Links to these domains and their subdomains are never written to history or usage data",
+            Row::new()
+                .spacing(6)
+                .align_y(Alignment::Center)
+                .push(
+                    text_input("bank.com", excluded_domain_text)
+                        .on_input(type_excluded_domain)
+                        .on_submit(add_excluded_domain.clone())
+                        .size(12)
+                        .width(Length::Fixed(230.0)),
+                )
+                .push(icon_button(
+                    ICON::Add,
+                    add_excluded_domain,
+                    "Add domain".to_string(),
+                )),
+        ));
+    for domain in excluded_domains {
+        content = content.push(preference_row(
+            domain,
+            "",
+            icon_button(
+                ICON::Remove,
+                remove_excluded_domain(domain.clone()),
+                "Remove domain".to_string(),
+            ),
+        ));
+    }
+    content = content.push(preference_row(
+        "Purge history",
+        "Deletes every recorded link and all usage history right away",
+        small_button("Purge", purge_history),
+    ));
+
+    container(scroll_view::scroll_view(content))
+        .style(|_| container::Style {
+            background: Some(Background::Color(Color::from_rgb(0.98, 0.98, 0.98))),
+            ..Default::default()
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+}
+
+fn small_button<'a, Message: 'a + Clone>(
+    label: &str,
+    on_press: Message,
+) -> button::Button<'a, Message> {
+    button(Text::new(label.to_string()).size(12))
+        .style(|_, _| button::Style {
+            background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+            border: Border {
+                radius: 4.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.9, 0.9, 0.9),
+            },
+            ..button::Style::default()
+        })
+        .padding([4, 12])
+        .on_press(on_press)
+}
+
+pub fn preference_row<'a, Message: 'a>(
+    title: &str,
+    description: &str,
+    control: impl Into<iced::Element<'a, Message>>,
+) -> Container<'a, Message> {
+    let label = Column::new()
+        .spacing(4)
+        .width(Length::Fill)
+        .push(
+            Text::new(title.to_string())
+                .size(13)
+                .style(|_| text::Style {
+                    color: Some(Color::from_rgb(0.2, 0.2, 0.2)),
+                }),
+        )
+        .push_maybe((!description.is_empty()).then(|| {
+            Text::new(description.to_string())
+                .size(11)
+                .style(|_| text::Style {
+                    color: Some(Color::from_rgb(0.5, 0.5, 0.5)),
+                })
+        }));
+    container(
+        Row::new()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(label)
+            .push(control),
+    )
+    .style(|_| container::Style {
+        background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+        border: Border {
+            radius: 4.0.into(),
+            width: 1.0,
+            color: Color::from_rgb(0.95, 0.95, 0.95),
+        },
+        ..Default::default()
+    })
+    .padding(8)
+}
@@ -0,0 +1,38 @@
+use iced::{
+    widget::{button, container, text, Container, Text},
+    Background, Border, Color, Length,
+};
+
+pub fn route_hud<'a, Message: 'a + Clone>(
+    message: String,
+    change: Message,
+) -> Container<'a, Message> {
+    container(
+        button(
+            container(Text::new(message).size(12).style(|_| text::Style {
+                color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
+            }))
+            .center_y(Length::Fill),
+        )
+        .on_press(change)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding([0, 14])
+        .style(|_, status| button::Style {
+            background: Some(Background::Color(match status {
+                button::Status::Hovered | button::Status::Pressed => {
+                    Color::from_rgb(0.25, 0.25, 0.3)
+                }
+                _ => Color::from_rgb(0.15, 0.15, 0.18),
+            })),
+            border: Border {
+                radius: 8.0.into(),
+                width: 0.0,
+                color: Color::TRANSPARENT,
+            },
+            ..button::Style::default()
+        }),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+}
@@ -1,31 +1,37 @@
 use iced::{
     widget::{
-        container,
+        button, checkbox, container,
         image::{self},
-        text, tooltip, Column, Container, Row, Text,
+        pick_list, text, tooltip, Column, Container, Row, Text,
     },
-    Alignment, Background, Border, Color, Length, Padding, Shadow, Theme,
+    Alignment, Background, Border, Color, Element, Length, Padding, Shadow, Theme,
 };
 use std::collections::HashMap;
+use std::path::Path;
 
 use super::components::icon_button::{icon_button, ICON};
 use super::components::scroll_view;
+use crate::actions;
+use crate::message::RuleTarget;
 use crate::storage::{BrowserInfo, MatchItem};
 
 const SPACING: f32 = 10.0;
 const APP_WIDTH: f32 = 80.0;
 const MATCH_TYPE_WIDTH: f32 = 80.0;
 const PROFILE_WIDTH: f32 = 100.0;
+const PRIVATE_WIDTH: f32 = 60.0;
 const ACTION_WIDTH: f32 = 80.0;
 
 pub fn rule_manager<'a, Message: 'a + Clone>(
     match_items: Vec<MatchItem>,
     browser_list: Vec<BrowserInfo>,
+    orphaned_match_values: Vec<String>,
     delete_match_item: impl Fn(String) -> Message,
+    toggle_private: impl Fn(String, bool) -> Message,
 ) -> Container<'a, Message> {
     let browser_map: HashMap<String, BrowserInfo> = browser_list
         .into_iter()
-        .map(|browser| (browser.path.clone(), browser))
+        .map(|browser| (browser.bundle_id.clone(), browser))
         .collect();
 
     let mut content = Column::new().spacing(5).padding(15).width(Length::Fill);
@@ -55,6 +61,11 @@ pub fn rule_manager<'a, Message: 'a + Clone>(
                 .width(Length::Fixed(PROFILE_WIDTH))
                 .center_y(Length::Fill),
         )
+        .push(
+            container(Text::new("Private").size(12).style(header_text_style()))
+                .width(Length::Fixed(PRIVATE_WIDTH))
+                .center_y(Length::Fill),
+        )
         .push(
             container(Text::new("Action").size(12).style(header_text_style()))
                 .width(Length::Fixed(ACTION_WIDTH))
@@ -77,111 +88,141 @@ pub fn rule_manager<'a, Message: 'a + Clone>(
 
     // Add items with better styling
     for item in match_items {
-        let browser = browser_map.get(&item.browser_path);
-        let row =
-            Row::new()
-                .spacing(SPACING)
-                .align_y(Alignment::Center)
-                .push(
-                    container(
-                        image::viewer(image::Handle::from_bytes(
-                            browser.unwrap().icon_data.clone(),
-                        ))
-                        .width(Length::Fixed(16.0))
-                        .height(Length::Fixed(16.0)),
-                    )
+        let browser = browser_map.get(&item.bundle_id);
+        let orphaned = orphaned_match_values.contains(&item.match_value);
+        let match_label = if item.is_hashed() {
+            format!(
+                "Exact URL (hashed) → {}",
+                browser
+                    .map(|browser| browser.name.as_str())
+                    .or(actions::display_name(&item.bundle_id))
+                    .unwrap_or(&item.browser_path)
+            )
+        } else {
+            item.match_value.clone()
+        };
+        let row = Row::new()
+            .spacing(SPACING)
+            .align_y(Alignment::Center)
+            .push(
+                container(browser_icon(browser, &item.bundle_id, &item.browser_path))
                     .padding(Padding::new(0.0).left(5.0))
                     .width(Length::Fixed(APP_WIDTH)),
-                )
-                .push(tooltip(
-                    Text::new(truncate_string(&item.match_value.clone(), 80))
-                        .size(11)
-                        .width(Length::Fill)
-                        .style(|_| text::Style {
-                            color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
-                            ..Default::default()
-                        }),
-                    container(Text::new(item.match_value.clone()).size(11).style(|_| {
-                        text::Style {
-                            color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
-                            ..Default::default()
-                        }
-                    }))
-                    .style(|_| container::Style {
-                        background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
-                        border: Border {
-                            radius: 4.0.into(),
-                            width: 1.0,
-                            color: Color::from_rgb(0.9, 0.9, 0.9),
-                        },
-                        shadow: Shadow {
-                            offset: iced::Vector::new(0.0, 2.0),
-                            blur_radius: 5.0,
-                            color: Color::from_rgba(0.0, 0.0, 0.0, 0.1),
-                        },
-                        ..Default::default()
-                    })
-                    .padding(8),
-                    tooltip::Position::Top,
-                ))
-                .push(
-                    container(
-                        Text::new(item.match_type.to_lowercase())
+            )
+            .push(tooltip(
+                Row::new()
+                    .spacing(6)
+                    .align_y(Alignment::Center)
+                    .width(Length::Fill)
+                    .push(
+                        Text::new(truncate_string(&match_label, 80))
                             .size(11)
                             .style(|_| text::Style {
                                 color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
                                 ..Default::default()
                             }),
                     )
-                    .style(move |_| container::Style {
-                        background: Some(Background::Color(if item.match_type == "Equal" {
-                            Color::from_rgb(0.95, 0.97, 1.0)
+                    .push_maybe(item.auto_created.then(|| {
+                        Text::new("auto-created").size(10).style(|_| text::Style {
+                            color: Some(Color::from_rgb(0.5, 0.5, 0.5)),
+                        })
+                    })),
+                container(Text::new(match_label).size(11).style(|_| text::Style {
+                    color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
+                    ..Default::default()
+                }))
+                .style(|_| container::Style {
+                    background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+                    border: Border {
+                        radius: 4.0.into(),
+                        width: 1.0,
+                        color: Color::from_rgb(0.9, 0.9, 0.9),
+                    },
+                    shadow: Shadow {
+                        offset: iced::Vector::new(0.0, 2.0),
+                        blur_radius: 5.0,
+                        color: Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+                    },
+                    ..Default::default()
+                })
+                .padding(8),
+                tooltip::Position::Top,
+            ))
+            .push(
+                container(
+                    Text::new(item.match_type.to_lowercase())
+                        .size(11)
+                        .style(|_| text::Style {
+                            color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
+                            ..Default::default()
+                        }),
+                )
+                .style(move |_| container::Style {
+                    background: Some(Background::Color(if item.match_type == "Equal" {
+                        Color::from_rgb(0.95, 0.97, 1.0)
+                    } else {
+                        Color::from_rgb(1.0, 0.97, 0.95)
+                    })),
+                    border: Border {
+                        radius: 4.0.into(),
+                        width: 1.0,
+                        color: if item.match_type == "Equal" {
+                            Color::from_rgb(0.8, 0.9, 1.0)
                         } else {
-                            Color::from_rgb(1.0, 0.97, 0.95)
-                        })),
-                        border: Border {
-                            radius: 4.0.into(),
-                            width: 1.0,
-                            color: if item.match_type == "Equal" {
-                                Color::from_rgb(0.8, 0.9, 1.0)
-                            } else {
-                                Color::from_rgb(1.0, 0.9, 0.8)
-                            },
+                            Color::from_rgb(1.0, 0.9, 0.8)
                         },
-                        ..Default::default()
-                    })
-                    .width(Length::Fixed(MATCH_TYPE_WIDTH))
-                    .center_x(MATCH_TYPE_WIDTH)
-                    .padding(5),
+                    },
+                    ..Default::default()
+                })
+                .width(Length::Fixed(MATCH_TYPE_WIDTH))
+                .center_x(MATCH_TYPE_WIDTH)
+                .padding(5),
+            )
+            .push(
+                container(
+                    Text::new(item.profile.unwrap_or_default())
+                        .size(11)
+                        .style(|_| text::Style {
+                            color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
+                            ..Default::default()
+                        }),
                 )
-                .push(
-                    container(
-                        Text::new(item.profile.unwrap_or_default())
-                            .size(11)
-                            .style(|_| text::Style {
-                                color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
-                                ..Default::default()
-                            }),
-                    )
-                    .width(Length::Fixed(PROFILE_WIDTH)),
+                .width(Length::Fixed(PROFILE_WIDTH)),
+            )
+            .push({
+                let toggle = toggle_private(item.match_value.clone(), !item.private);
+                container(
+                    checkbox("", item.private)
+                        .size(14)
+                        .on_toggle(move |_| toggle.clone()),
                 )
-                .push(
-                    container(icon_button(
-                        ICON::Remove,
-                        delete_match_item(item.match_value.clone()),
-                        "Delete rule".to_string(),
-                    ))
-                    .width(Length::Fixed(ACTION_WIDTH)),
-                );
+                .width(Length::Fixed(PRIVATE_WIDTH))
+            })
+            .push(
+                container(icon_button(
+                    ICON::Remove,
+                    delete_match_item(item.match_value.clone()),
+                    "Delete rule".to_string(),
+                ))
+                .width(Length::Fixed(ACTION_WIDTH)),
+            );
 
         content = content.push(
             container(row)
-                .style(|_| container::Style {
-                    background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+                .style(move |_| container::Style {
+                    background: Some(Background::Color(if orphaned {
+                        Color::from_rgb(1.0, 0.96, 0.88)
+                    } else {
+                        Color::from_rgb(1.0, 1.0, 1.0)
+                    })),
                     border: Border {
                         radius: 4.0.into(),
                         width: 1.0,
-                        color: Color::from_rgb(0.95, 0.95, 0.95),
+                        color: if orphaned {
+                            Color::from_rgb(0.9, 0.8, 0.6)
+                        } else {
+                            Color::from_rgb(0.95, 0.95, 0.95)
+                        },
                     },
                     ..Default::default()
                 })
@@ -198,7 +239,129 @@ pub fn rule_manager<'a, Message: 'a + Clone>(
         .height(Length::Fill)
 }
 
-fn truncate_string(s: &str, max_chars: usize) -> String {
+#[allow(clippy::too_many_arguments)]
+pub fn orphan_repair<'a, Message: 'a + Clone>(
+    orphaned_rule_count: usize,
+    orphaned_profile_count: usize,
+    rule_targets: Vec<RuleTarget>,
+    rule_target: Option<RuleTarget>,
+    select_rule_target: impl Fn(RuleTarget) -> Message + 'a,
+    reassign_rules: Message,
+    delete_rules: Message,
+    delete_profiles: Message,
+) -> Container<'a, Message> {
+    let mut content = Column::new().spacing(8);
+    if orphaned_rule_count > 0 {
+        content = content.push(
+            Row::new()
+                .spacing(SPACING)
+                .align_y(Alignment::Center)
+                .push(
+                    Text::new(format!(
+                        "{} rule{} point to a browser or profile that no longer exists.",
+                        orphaned_rule_count,
+                        if orphaned_rule_count == 1 { "" } else { "s" }
+                    ))
+                    .size(12)
+                    .width(Length::Fill)
+                    .style(warning_text_style()),
+                )
+                .push(
+                    pick_list(rule_targets, rule_target.clone(), select_rule_target)
+                        .placeholder("Reassign to...")
+                        .text_size(12)
+                        .width(Length::Fixed(220.0)),
+                )
+                .push(repair_button(
+                    "Reassign",
+                    rule_target.is_some().then_some(reassign_rules),
+                ))
+                .push(repair_button("Delete", Some(delete_rules))),
+        );
+    }
+    if orphaned_profile_count > 0 {
+        content = content.push(
+            Row::new()
+                .spacing(SPACING)
+                .align_y(Alignment::Center)
+                .push(
+                    Text::new(format!(
+                        "{} saved profile{} belong to browsers that are no longer installed.",
+                        orphaned_profile_count,
+                        if orphaned_profile_count == 1 { "" } else { "s" }
+                    ))
+                    .size(12)
+                    .width(Length::Fill)
+                    .style(warning_text_style()),
+                )
+                .push(repair_button("Delete", Some(delete_profiles))),
+        );
+    }
+    container(content)
+        .padding([10, 15])
+        .width(Length::Fill)
+        .style(|_| container::Style {
+            background: Some(Background::Color(Color::from_rgb(1.0, 0.96, 0.88))),
+            ..Default::default()
+        })
+}
+
+fn repair_button<'a, Message: 'a + Clone>(
+    label: &str,
+    on_press: Option<Message>,
+) -> button::Button<'a, Message> {
+    button(Text::new(label.to_string()).size(12))
+        .style(|_, _| button::Style {
+            background: Some(Background::Color(Color::from_rgb(1.0, 1.0, 1.0))),
+            border: Border {
+                radius: 4.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.9, 0.8, 0.6),
+            },
+            ..button::Style::default()
+        })
+        .padding([4, 12])
+        .on_press_maybe(on_press)
+}
+
+fn browser_icon<'a, Message: 'a>(
+    browser: Option<&BrowserInfo>,
+    bundle_id: &str,
+    browser_path: &str,
+) -> Element<'a, Message> {
+    let name = browser
+        .map(|browser| browser.name.as_str())
+        .or(actions::display_name(bundle_id));
+    match browser {
+        Some(browser) if !browser.icon_data.is_empty() => {
+            image::viewer(image::Handle::from_bytes(browser.icon_data.clone()))
+                .width(Length::Fixed(16.0))
+                .height(Length::Fixed(16.0))
+                .into()
+        }
+        _ if name.is_some() => Text::new(truncate_string(name.unwrap(), 10))
+            .size(11)
+            .style(|_| text::Style {
+                color: Some(Color::from_rgb(0.3, 0.3, 0.3)),
+            })
+            .into(),
+        _ => {
+            let name = Path::new(browser_path)
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .unwrap_or(browser_path)
+                .to_string();
+            Text::new(truncate_string(&name, 10))
+                .size(11)
+                .style(|_| text::Style {
+                    color: Some(Color::from_rgb(0.8, 0.3, 0.2)),
+                })
+                .into()
+        }
+    }
+}
+
+pub fn truncate_string(s: &str, max_chars: usize) -> String {
     if s.chars().count() <= max_chars {
         s.to_string()
     } else {
@@ -206,7 +369,13 @@ fn truncate_string(s: &str, max_chars: usize) -> String {
     }
 }
 
-fn header_text_style() -> impl Fn(&Theme) -> text::Style {
+fn warning_text_style() -> impl Fn(&Theme) -> text::Style {
+    |_| text::Style {
+        color: Some(Color::from_rgb(0.55, 0.25, 0.0)),
+    }
+}
+
+pub fn header_text_style() -> impl Fn(&Theme) -> text::Style {
     |_| text::Style {
         color: Some(Color::from_rgb(0.4, 0.4, 0.4)),
         ..Default::default()
@@ -1,46 +1,105 @@
 use iced::{
     widget::{
         container,
-        image::{self}, Column, Container, Text,
+        image::{self},
+        text, Column, Container, Text,
     },
     Background, Color, Length,
 };
 
-use crate::storage::BrowserInfo;
+use crate::actions::Action;
+use crate::storage::{BrowserInfo, BrowserProfile};
 
 use super::components::{browser_list_item, icon_button, scroll_view};
 
+#[derive(Debug, Clone)]
+pub enum PickerEntry {
+    Browser(BrowserInfo),
+    Profile(BrowserInfo, BrowserProfile),
+    Action(Action),
+}
+
+impl PickerEntry {
+    pub fn label(&self) -> String {
+        match self {
+            PickerEntry::Browser(browser) => browser.name.clone(),
+            PickerEntry::Profile(browser, profile) => format!(
+                "{} · {}",
+                browser.name,
+                profile.description.as_ref().unwrap_or(&profile.profile)
+            ),
+            PickerEntry::Action(action) => action.name().to_string(),
+        }
+    }
+
+    pub fn target(&self) -> (String, Option<String>) {
+        match self {
+            PickerEntry::Browser(browser) => (browser.bundle_id.clone(), None),
+            PickerEntry::Profile(browser, profile) => {
+                (browser.bundle_id.clone(), Some(profile.profile.clone()))
+            }
+            PickerEntry::Action(action) => (action.id().to_string(), None),
+        }
+    }
+}
+
 pub fn select_browser<'a, Message: 'a + Clone>(
-    browsers: &Vec<BrowserInfo>,
+    entries: &[PickerEntry],
+    filter: &str,
+    selected: usize,
+    suggested: Option<(String, Option<String>)>,
     select_browser: impl Fn(String) -> Message,
+    select_profile: impl Fn(String, String) -> Message,
     list_profiles: impl Fn(BrowserInfo) -> Message,
 ) -> Container<'a, Message> {
     let mut content = Column::new().spacing(8).padding(12).width(Length::Fill);
-    content = content.push(
-        container(Text::new(""))
-            .width(Length::Fill)
-            .height(1)
-            .style(|_| container::Style {
-                background: Some(Background::Color(Color::from_rgb(0.9, 0.9, 0.9))),
-                ..Default::default()
-            }),
-    );
-
-    for browser in browsers {
-        let name = browser.name.to_string();
-        let path = browser.path.clone();
-        let icon = image::viewer(image::Handle::from_bytes(browser.icon_data.clone()))
-            .width(Length::Fixed(16.0))
-            .height(Length::Fixed(16.0));
-
-        content = content.push(browser_list_item::browser_list_item(
-            Some(icon),
-            name,
-            select_browser(path.clone()),
-            icon_button::ICON::Profile,
-            list_profiles(browser.clone()),
-            "List profiles".to_string(),
-        ));
+    if !filter.is_empty() {
+        content = content.push(hint_text(format!("Search: {}", filter)));
+    }
+    content = content.push(divider());
+    if entries.is_empty() && !filter.is_empty() {
+        content = content.push(hint_text("No matches".to_string()));
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let is_selected = index == selected;
+        let badge = (suggested.as_ref() == Some(&entry.target())).then(|| "suggested".to_string());
+        let item = match entry {
+            PickerEntry::Browser(browser) => browser_list_item::browser_list_item(
+                browser_icon(browser),
+                entry.label(),
+                select_browser(browser.bundle_id.clone()),
+                Some((
+                    icon_button::ICON::Profile,
+                    list_profiles(browser.clone()),
+                    "List profiles".to_string(),
+                )),
+                badge,
+                is_selected,
+            ),
+            PickerEntry::Profile(browser, profile) => browser_list_item::browser_list_item(
+                browser_icon(browser),
+                entry.label(),
+                select_profile(browser.bundle_id.clone(), profile.profile.clone()),
+                None,
+                badge,
+                is_selected,
+            ),
+            PickerEntry::Action(action) => {
+                if index > 0 && !matches!(entries[index - 1], PickerEntry::Action(_)) {
+                    content = content.push(divider());
+                }
+                browser_list_item::browser_list_item(
+                    None,
+                    entry.label(),
+                    select_browser(action.id().to_string()),
+                    None,
+                    badge,
+                    is_selected,
+                )
+            }
+        };
+        content = content.push(item);
     }
 
     container(
@@ -58,3 +117,27 @@ pub fn select_browser<'a, Message: 'a + Clone>(
     .width(Length::Fill)
     .height(Length::Fill)
 }
+
+fn browser_icon(browser: &BrowserInfo) -> Option<image::Viewer<image::Handle>> {
+    (!browser.icon_data.is_empty()).then(|| {
+        image::viewer(image::Handle::from_bytes(browser.icon_data.clone()))
+            .width(Length::Fixed(16.0))
+            .height(Length::Fixed(16.0))
+    })
+}
+
+fn hint_text<'a>(hint: String) -> Text<'a> {
+    Text::new(hint).size(12).style(|_| text::Style {
+        color: Some(Color::from_rgb(0.5, 0.5, 0.5)),
+    })
+}
+
+fn divider<'a, Message: 'a>() -> Container<'a, Message> {
+    container(Text::new(""))
+        .width(Length::Fill)
+        .height(1)
+        .style(|_| container::Style {
+            background: Some(Background::Color(Color::from_rgb(0.9, 0.9, 0.9))),
+            ..Default::default()
+        })
+}
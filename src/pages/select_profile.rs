@@ -1,8 +1,5 @@
 use iced::{
-    widget::{
-        container,
-        row, text_input, Column, Container,
-    },
+    widget::{container, row, text_input, Column, Container},
     Alignment, Length, Padding,
 };
 
@@ -12,6 +9,7 @@ use super::components::{browser_list_item, icon_button, scroll_view};
 
 pub fn select_profile<'a, Message: 'a + Clone>(
     profiles: &Vec<BrowserProfile>,
+    selected: usize,
     open_browser_with_profile: impl Fn(String) -> Message,
     delete_profile: impl Fn(String) -> Message,
     back: Message,
@@ -24,19 +22,30 @@ pub fn select_profile<'a, Message: 'a + Clone>(
         .width(Length::Fill)
         .height(Length::Fill);
 
-    let profile_list = profiles
-        .iter()
-        .fold(Column::new().spacing(5), |column, profile| {
-            let profile_row = browser_list_item::browser_list_item(
-                None,
-                profile.profile.clone(),
-                open_browser_with_profile(profile.profile.clone()),
-                icon_button::ICON::Remove,
-                delete_profile(profile.profile.clone()),
-                "Delete profile".to_string(),
-            );
-            column.push(profile_row)
-        });
+    let profile_list =
+        profiles
+            .iter()
+            .enumerate()
+            .fold(Column::new().spacing(5), |column, (index, profile)| {
+                let name = profile.description.as_ref().unwrap_or(&profile.profile);
+                let label = match &profile.account {
+                    Some(account) => format!("{} ({})", name, account),
+                    None => name.clone(),
+                };
+                let profile_row = browser_list_item::browser_list_item(
+                    None,
+                    label,
+                    open_browser_with_profile(profile.profile.clone()),
+                    Some((
+                        icon_button::ICON::Remove,
+                        delete_profile(profile.profile.clone()),
+                        "Delete profile".to_string(),
+                    )),
+                    None,
+                    index == selected,
+                );
+                column.push(profile_row)
+            });
     content = content.push(
         container(scroll_view::scroll_view(profile_list))
             .height(Length::Fill)
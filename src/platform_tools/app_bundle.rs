@@ -0,0 +1,350 @@
+use plist::{Dictionary, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PREFERRED_ICON_SIZE: u32 = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppBundle {
+    pub path: PathBuf,
+    pub bundle_id: Option<String>,
+    pub display_name: String,
+    pub url_schemes: Vec<String>,
+    pub icon_file: Option<String>,
+}
+
+impl AppBundle {
+    pub fn read(path: &Path) -> Option<AppBundle> {
+        let info = Value::from_file(path.join("Contents/Info.plist")).ok()?;
+        Some(Self::from_info(path, info.as_dictionary()?))
+    }
+
+    fn from_info(path: &Path, info: &Dictionary) -> AppBundle {
+        let string = |key: &str| {
+            info.get(key)
+                .and_then(Value::as_string)
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string())
+        };
+        let display_name = string("CFBundleDisplayName")
+            .or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+            })
+            .or_else(|| string("CFBundleName"))
+            .unwrap_or_default();
+        let url_schemes = info
+            .get("CFBundleURLTypes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|url_type| url_type.as_dictionary()?.get("CFBundleURLSchemes"))
+            .filter_map(Value::as_array)
+            .flatten()
+            .filter_map(Value::as_string)
+            .map(|scheme| scheme.to_lowercase())
+            .collect();
+
+        AppBundle {
+            path: path.to_path_buf(),
+            bundle_id: string("CFBundleIdentifier"),
+            display_name,
+            url_schemes,
+            icon_file: string("CFBundleIconFile"),
+        }
+    }
+
+    pub fn supports_http(&self) -> bool {
+        self.url_schemes
+            .iter()
+            .any(|scheme| scheme == "http" || scheme == "https")
+    }
+
+    pub fn icon_path(&self) -> Option<PathBuf> {
+        let icon_file = self.icon_file.as_ref()?;
+        let mut path = self.path.join("Contents/Resources").join(icon_file);
+        if path.extension().is_none() {
+            path.set_extension("icns");
+        }
+        Some(path)
+    }
+
+    pub fn icon_png(&self) -> Option<Vec<u8>> {
+        decode_icns(&fs::read(self.icon_path()?).ok()?)
+    }
+}
+
+pub fn decode_icns(data: &[u8]) -> Option<Vec<u8>> {
+    let entries = icns_entries(data)?;
+
+    let mut pngs: Vec<(u32, &[u8])> = entries
+        .iter()
+        .filter(|(_, body)| body.starts_with(PNG_SIGNATURE) && body.len() >= 24)
+        .map(|(_, body)| {
+            let width = u32::from_be_bytes([body[16], body[17], body[18], body[19]]);
+            (width, *body)
+        })
+        .collect();
+    pngs.sort_by_key(|(width, _)| *width);
+    if let Some((_, png)) = pngs
+        .iter()
+        .find(|(width, _)| *width >= PREFERRED_ICON_SIZE)
+        .or(pngs.last())
+    {
+        return Some(png.to_vec());
+    }
+
+    let entry = |kind: &[u8]| {
+        entries
+            .iter()
+            .find(|(entry_kind, _)| *entry_kind == kind)
+            .map(|(_, body)| *body)
+    };
+    for (kind, size) in [(b"ic05", 32), (b"ic04", 16)] {
+        if let Some(body) = entry(kind).and_then(|body| body.strip_prefix(b"ARGB")) {
+            let channels = unpack_rle(body, (size * size) as usize, 4)?;
+            return Some(encode_png(size, size, &interleave(&channels, 4, None)));
+        }
+    }
+    for (kind, mask_kind, size) in [(b"il32", b"l8mk", 32), (b"is32", b"s8mk", 16)] {
+        if let Some(body) = entry(kind) {
+            let pixels = (size * size) as usize;
+            let mask = entry(mask_kind).filter(|mask| mask.len() == pixels);
+            let channels = unpack_rle(body, pixels, 3)?;
+            return Some(encode_png(size, size, &interleave(&channels, 3, mask)));
+        }
+    }
+    None
+}
+
+fn icns_entries(data: &[u8]) -> Option<Vec<(&[u8], &[u8])>> {
+    if data.len() < 8 || &data[0..4] != b"icns" {
+        return None;
+    }
+    let total = (u32::from_be_bytes(data[4..8].try_into().ok()?) as usize).min(data.len());
+    let mut entries = Vec::new();
+    let mut offset = 8;
+    while offset + 8 <= total {
+        let kind = &data[offset..offset + 4];
+        let length = u32::from_be_bytes(data[offset + 4..offset + 8].try_into().ok()?) as usize;
+        if length < 8 || offset + length > total {
+            break;
+        }
+        entries.push((kind, &data[offset + 8..offset + length]));
+        offset += length;
+    }
+    Some(entries)
+}
+
+fn unpack_rle(data: &[u8], pixels: usize, channels: usize) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(pixels * channels);
+    let mut input = data.iter();
+    while output.len() < pixels * channels {
+        let control = *input.next()? as usize;
+        if control < 0x80 {
+            for _ in 0..=control {
+                output.push(*input.next()?);
+            }
+        } else {
+            let value = *input.next()?;
+            output.resize(output.len() + control - 125, value);
+        }
+    }
+    output.truncate(pixels * channels);
+    Some(output)
+}
+
+fn interleave(channels: &[u8], count: usize, mask: Option<&[u8]>) -> Vec<u8> {
+    let pixels = channels.len() / count;
+    let mut rgba = Vec::with_capacity(pixels * 4);
+    for i in 0..pixels {
+        let channel = |index: usize| channels[index * pixels + i];
+        if count == 4 {
+            rgba.extend([channel(1), channel(2), channel(3), channel(0)]);
+        } else {
+            let alpha = mask.map(|mask| mask[i]).unwrap_or(255);
+            rgba.extend([channel(0), channel(1), channel(2), alpha]);
+        }
+    }
+    rgba
+}
+
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(rgba))
+        .unwrap();
+    png
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_app(root: &Path, name: &str, info: Dictionary, binary: bool) -> PathBuf {
+        let app = root.join(name);
+        fs::create_dir_all(app.join("Contents/Resources")).unwrap();
+        let plist_path = app.join("Contents/Info.plist");
+        if binary {
+            Value::Dictionary(info).to_file_binary(plist_path).unwrap();
+        } else {
+            Value::Dictionary(info).to_file_xml(plist_path).unwrap();
+        }
+        app
+    }
+
+    fn browser_info(bundle_id: &str, schemes: &[&str]) -> Dictionary {
+        let mut url_type = Dictionary::new();
+        url_type.insert(
+            "CFBundleURLSchemes".to_string(),
+            Value::Array(schemes.iter().map(|s| Value::from(*s)).collect()),
+        );
+        let mut info = Dictionary::new();
+        info.insert("CFBundleIdentifier".to_string(), Value::from(bundle_id));
+        info.insert(
+            "CFBundleURLTypes".to_string(),
+            Value::Array(vec![Value::Dictionary(url_type)]),
+        );
+        info
+    }
+
+    fn icns(entries: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (kind, data) in entries {
+            body.extend_from_slice(*kind);
+            body.extend((data.len() as u32 + 8).to_be_bytes());
+            body.extend_from_slice(data);
+        }
+        let mut icns = b"icns".to_vec();
+        icns.extend((body.len() as u32 + 8).to_be_bytes());
+        icns.extend(body);
+        icns
+    }
+
+    fn png_stub(width: u32) -> Vec<u8> {
+        encode_png(width, 1, &vec![0; width as usize * 4])
+    }
+
+    #[test]
+    fn test_read_xml_and_binary_info_plist() {
+        let root = std::env::temp_dir().join("gomi-app-bundle-test");
+        let _ = fs::remove_dir_all(&root);
+
+        let mut info = browser_info("org.mozilla.firefox", &["HTTP", "https", "file"]);
+        info.insert("CFBundleName".to_string(), Value::from("Firefox"));
+        info.insert("CFBundleIconFile".to_string(), Value::from("firefox"));
+        let firefox = create_app(&root, "Firefox Nightly.app", info, false);
+
+        let mut info = browser_info("com.google.Chrome", &["https"]);
+        info.insert("CFBundleDisplayName".to_string(), Value::from("Chrome"));
+        info.insert("CFBundleIconFile".to_string(), Value::from("app.icns"));
+        let chrome = create_app(&root, "Google Chrome.app", info, true);
+
+        let mut info = browser_info("com.example.mail", &["mailto"]);
+        info.insert("CFBundleDisplayName".to_string(), Value::from(""));
+        let mail = create_app(&root, "Mail.app", info, true);
+
+        fs::create_dir_all(root.join("Broken.app/Contents")).unwrap();
+        fs::write(root.join("Broken.app/Contents/Info.plist"), "not a plist").unwrap();
+
+        let bundle = AppBundle::read(&firefox).unwrap();
+        assert_eq!(bundle.bundle_id.as_deref(), Some("org.mozilla.firefox"));
+        assert_eq!(bundle.display_name, "Firefox Nightly");
+        assert_eq!(bundle.url_schemes, vec!["http", "https", "file"]);
+        assert!(bundle.supports_http());
+        assert_eq!(
+            bundle.icon_path(),
+            Some(firefox.join("Contents/Resources/firefox.icns"))
+        );
+
+        let bundle = AppBundle::read(&chrome).unwrap();
+        assert_eq!(bundle.bundle_id.as_deref(), Some("com.google.Chrome"));
+        assert_eq!(bundle.display_name, "Chrome");
+        assert!(bundle.supports_http());
+        assert_eq!(
+            bundle.icon_path(),
+            Some(chrome.join("Contents/Resources/app.icns"))
+        );
+        assert_eq!(bundle.icon_png(), None);
+
+        let bundle = AppBundle::read(&mail).unwrap();
+        assert_eq!(bundle.display_name, "Mail");
+        assert!(!bundle.supports_http());
+        assert_eq!(bundle.icon_path(), None);
+
+        assert_eq!(AppBundle::read(&root.join("Broken.app")), None);
+        assert_eq!(AppBundle::read(&root.join("Missing.app")), None);
+
+        fs::write(
+            firefox.join("Contents/Resources/firefox.icns"),
+            icns(&[(b"ic07", png_stub(128)), (b"ic11", png_stub(32))]),
+        )
+        .unwrap();
+        assert_eq!(
+            AppBundle::read(&firefox).unwrap().icon_png(),
+            Some(png_stub(32))
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_decode_icns_png_entries() {
+        let data = icns(&[
+            (b"TOC ", vec![0; 8]),
+            (b"icp4", png_stub(16)),
+            (b"ic08", png_stub(256)),
+            (b"icp6", png_stub(64)),
+        ]);
+        assert_eq!(decode_icns(&data), Some(png_stub(64)));
+
+        let data = icns(&[(b"icp4", png_stub(16))]);
+        assert_eq!(decode_icns(&data), Some(png_stub(16)));
+
+        assert_eq!(decode_icns(b"not an icon"), None);
+        assert_eq!(decode_icns(&icns(&[])), None);
+    }
+
+    #[test]
+    fn test_decode_icns_legacy_entries() {
+        // each channel is 256 bytes, packed as runs of 130 + 126 (or literals)
+        let mut rgb = vec![0x01, 0xff, 0xff, 0xff, 0xff, 0xf9, 0xff];
+        for value in [0x80, 0x00] {
+            rgb.extend([0xff, value, 0xfb, value]);
+        }
+        let data = icns(&[(b"is32", rgb), (b"s8mk", vec![0x40; 256])]);
+        let mut expected = Vec::new();
+        for _ in 0..256 {
+            expected.extend([0xff, 0x80, 0x00, 0x40]);
+        }
+        assert_eq!(decode_icns(&data), Some(encode_png(16, 16, &expected)));
+
+        let mut argb = b"ARGB".to_vec();
+        for value in [0x11, 0x22, 0x33, 0x44] {
+            argb.extend([0xff, value, 0xfb, value]);
+        }
+        let data = icns(&[(b"ic04", argb)]);
+        let mut expected = Vec::new();
+        for _ in 0..256 {
+            expected.extend([0x22, 0x33, 0x44, 0x11]);
+        }
+        assert_eq!(decode_icns(&data), Some(encode_png(16, 16, &expected)));
+    }
+
+    #[test]
+    fn test_encode_png() {
+        let png = encode_png(2, 1, &[0xff, 0x00, 0x00, 0xff, 0x00, 0x00, 0xff, 0x80]);
+        assert!(png.starts_with(PNG_SIGNATURE));
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut rgba = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut rgba).unwrap();
+        assert_eq!((info.width, info.height), (2, 1));
+        assert_eq!(info.color_type, png::ColorType::Rgba);
+        assert_eq!(rgba, [0xff, 0x00, 0x00, 0xff, 0x00, 0x00, 0xff, 0x80]);
+    }
+}
@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::get_data_dir;
+
+const MAX_NESTING: usize = 2;
+
+pub fn search_dirs(custom_dirs: &[String]) -> Vec<PathBuf> {
+    let home = get_data_dir();
+    let mut dirs = vec![
+        PathBuf::from("/Applications"),
+        Path::new(&home).join("Applications"),
+        PathBuf::from("/System/Applications"),
+        PathBuf::from("/Applications/Setapp"),
+    ];
+    for dir in custom_dirs {
+        let dir = expand_home(dir);
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => Path::new(&get_data_dir()).join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+pub fn find_app_bundles(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut result = Vec::new();
+    for dir in dirs {
+        collect_app_bundles(dir, MAX_NESTING, &mut result);
+    }
+    result
+}
+
+fn collect_app_bundles(dir: &Path, depth: usize, result: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.extension().and_then(|s| s.to_str()) == Some("app") {
+            if !result.contains(&path) {
+                result.push(path);
+            }
+        } else if depth > 0 && path.is_dir() && !path.is_symlink() {
+            collect_app_bundles(&path, depth - 1, result);
+        }
+    }
+}
+
+pub fn display_location(app_path: &str) -> String {
+    let parent = Path::new(app_path)
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let home = get_data_dir();
+    match parent.strip_prefix(&home) {
+        Some(rest) if !home.is_empty() => format!("~{}", rest),
+        _ => parent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_app_bundles() {
+        let root = std::env::temp_dir().join("gomi-app-dirs-test");
+        let _ = fs::remove_dir_all(&root);
+        for dir in [
+            "Applications/Firefox.app/Contents",
+            "Applications/Setapp/Sigma.app/Contents",
+            "Applications/Vendor/Tools/Deep.app",
+            "Applications/Vendor/Tools/Deeper/TooDeep.app",
+            "Custom/Chromium.app",
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("Applications/readme.txt"), "").unwrap();
+
+        let apps = find_app_bundles(&[
+            root.join("Applications"),
+            root.join("Applications/Setapp"),
+            root.join("Custom"),
+            root.join("Missing"),
+        ]);
+        assert_eq!(
+            apps,
+            vec![
+                root.join("Applications/Firefox.app"),
+                root.join("Applications/Setapp/Sigma.app"),
+                root.join("Applications/Vendor/Tools/Deep.app"),
+                root.join("Custom/Chromium.app"),
+            ]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_search_dirs() {
+        let home = get_data_dir();
+        let dirs = search_dirs(&["~/Tools".to_string(), "/Applications".to_string()]);
+        assert_eq!(dirs[0], PathBuf::from("/Applications"));
+        assert!(dirs.contains(&Path::new(&home).join("Applications")));
+        assert_eq!(dirs.last(), Some(&Path::new(&home).join("Tools")));
+        assert_eq!(
+            dirs.iter()
+                .filter(|dir| *dir == Path::new("/Applications"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_display_location() {
+        let home = get_data_dir();
+        assert_eq!(
+            display_location(&format!("{}/Applications/Arc.app", home)),
+            "~/Applications"
+        );
+        assert_eq!(
+            display_location("/Applications/Setapp/Sigma.app"),
+            "/Applications/Setapp"
+        );
+    }
+}
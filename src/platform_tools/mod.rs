@@ -1,3 +1,6 @@
+pub mod app_bundle;
+pub mod app_dirs;
+
 use cocoa::appkit::{NSApplicationActivationPolicy, NSCompositingOperation};
 use cocoa::base::{id, nil};
 use cocoa::foundation::{NSPoint, NSRect, NSSize, NSString};
@@ -5,129 +8,105 @@ use core_foundation::{
     base::TCFType,
     string::{CFString, CFStringRef},
 };
+use iced::keyboard::Modifiers;
 use iced::Point;
 use objc::{class, msg_send, sel, sel_impl};
-use std::fs;
+use std::path::Path;
+use tracing::warn;
 
+use app_bundle::AppBundle;
 
+use crate::app_links::{self, APP_LINKS};
 use crate::config::{MENU_WINDOW_HEIGHT, MENU_WINDOW_WIDTH};
+use crate::launcher::{self, LaunchOptions};
 use crate::storage::BrowserInfo;
 
-pub  fn get_url_handlers() -> Vec<BrowserInfo> {
-
-        let mut result = Vec::new();
-        let app_dirs = ["/Applications"];
-
-        for app_dir in app_dirs {
-            if let Ok(entries) = fs::read_dir(app_dir) {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        let path = entry.path();
-                        let converted_path = String::from(path.to_str().unwrap());
-                        if path.extension().and_then(|s| s.to_str()) == Some("app") {
-                            unsafe {
-                                let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
-                                if workspace == nil {
-                                    continue;
-                                }
-
-                                let path_str = path.to_string_lossy();
-                                let ns_path = NSString::alloc(nil).init_str(&path_str);
-                                let bundle: id =
-                                    msg_send![class!(NSBundle), bundleWithPath: ns_path];
-                                if bundle == nil {
-                                    continue;
-                                }
-
-                                let schemes_key = NSString::alloc(nil).init_str("CFBundleURLTypes");
-                                let schemes: id =
-                                    msg_send![bundle, objectForInfoDictionaryKey: schemes_key];
-
-                                if schemes != nil {
-                                    let count: usize = msg_send![schemes, count];
-                                    let mut supports_http = false;
-
-                                    for i in 0..count {
-                                        let url_type: id = msg_send![schemes, objectAtIndex: i];
-                                        let schemes_key =
-                                            NSString::alloc(nil).init_str("CFBundleURLSchemes");
-                                        let url_schemes: id =
-                                            msg_send![url_type, objectForKey: schemes_key];
-
-                                        if url_schemes != nil {
-                                            let schemes_count: usize =
-                                                msg_send![url_schemes, count];
-                                            for j in 0..schemes_count {
-                                                let scheme: id =
-                                                    msg_send![url_schemes, objectAtIndex: j];
-                                                let scheme_str: id = msg_send![scheme, UTF8String];
-                                                if !scheme_str.is_null() {
-                                                    let scheme_rust = std::ffi::CStr::from_ptr(
-                                                        scheme_str as *const _,
-                                                    )
-                                                    .to_str()
-                                                    .unwrap_or("");
-                                                    if scheme_rust == "http"
-                                                        || scheme_rust == "https"
-                                                    {
-                                                        supports_http = true;
-                                                        break;
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if supports_http {
-                                            break;
-                                        }
-                                    }
-
-                                    if supports_http {
-                                        let file_manager: id =
-                                            msg_send![class!(NSFileManager), defaultManager];
-                                        if let Some(name) = get_app_name(file_manager, ns_path) {
-                                            let icon_data = get_app_icon(&path_str);
-                                            if !result
-                                                .iter()
-                                                .any(|app: &BrowserInfo| app.path == converted_path)
-                                                && icon_data.is_some()
-                                            {
-                                                result.push(BrowserInfo {
-                                                    name,
-                                                    path: converted_path,
-                                                    icon_data: icon_data.unwrap(),
-                                                });
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+pub fn get_url_handlers(custom_search_dirs: &[String]) -> Vec<BrowserInfo> {
+    let mut result: Vec<BrowserInfo> = Vec::new();
+    let mut scanned_bundles: Vec<(String, String)> = Vec::new();
+    let app_paths = app_dirs::find_app_bundles(&app_dirs::search_dirs(custom_search_dirs));
+
+    for path in app_paths {
+        let Some(bundle) = AppBundle::read(&path) else {
+            continue;
+        };
+        if let Some(bundle_id) = &bundle.bundle_id {
+            scanned_bundles.push((bundle_id.clone(), path.to_string_lossy().to_string()));
+        }
+        if !bundle.supports_http() {
+            continue;
         }
-        result
-}
 
-unsafe fn get_app_name(file_manager: id, path: id) -> Option<String> {
-    if file_manager == nil {
-        return None;
+        let path_str = path.to_string_lossy().to_string();
+        let Some(icon_data) = bundle.icon_png().or_else(|| get_app_icon(&path_str)) else {
+            continue;
+        };
+        let bundle_id = bundle.bundle_id.unwrap_or(path_str.clone());
+        if !result.iter().any(|app| app.bundle_id == bundle_id) {
+            result.push(BrowserInfo {
+                bundle_id,
+                name: bundle.display_name,
+                path: path_str,
+                icon_data,
+                launch_command: None,
+                hidden: false,
+            });
+        }
     }
-
-    let display_name: id = msg_send![file_manager, displayNameAtPath: path];
-    if display_name == nil {
-        return None;
+    for target in get_app_link_targets(&scanned_bundles) {
+        if !result.iter().any(|app| app.bundle_id == target.bundle_id) {
+            result.push(target);
+        }
     }
+    result
+}
 
-    let name_str: id = msg_send![display_name, UTF8String];
-    if name_str.is_null() {
-        return None;
-    }
+pub fn read_app_browser(app_path: &str) -> Option<BrowserInfo> {
+    let bundle = AppBundle::read(Path::new(app_path))?;
+    Some(BrowserInfo {
+        icon_data: bundle
+            .icon_png()
+            .or_else(|| get_app_icon(app_path))
+            .unwrap_or_default(),
+        bundle_id: bundle.bundle_id.unwrap_or(app_path.to_string()),
+        name: bundle.display_name,
+        path: app_path.to_string(),
+        launch_command: None,
+        hidden: false,
+    })
+}
 
-    std::ffi::CStr::from_ptr(name_str as *const _)
-        .to_str()
-        .ok()
-        .map(|s| s.to_owned())
+fn get_app_link_targets(scanned_bundles: &[(String, String)]) -> Vec<BrowserInfo> {
+    APP_LINKS
+        .iter()
+        .filter_map(|app_link| {
+            let path = scanned_bundles
+                .iter()
+                .find(|(bundle_id, _)| bundle_id == app_link.bundle_id)
+                .map(|(_, path)| path.clone())
+                .or_else(|| get_app_path(app_link.bundle_id))
+                .unwrap_or_else(|| app_link.path());
+            if !Path::new(&path).exists() {
+                return None;
+            }
+            let bundle = AppBundle::read(Path::new(&path));
+            let name = bundle
+                .as_ref()
+                .map(|bundle| bundle.display_name.clone())
+                .unwrap_or(app_link.name.to_string());
+            let icon_data = bundle
+                .and_then(|bundle| bundle.icon_png())
+                .or_else(|| get_app_icon(&path));
+            icon_data.map(|icon_data| BrowserInfo {
+                bundle_id: app_link.bundle_id.to_string(),
+                name,
+                path,
+                icon_data,
+                launch_command: None,
+                hidden: false,
+            })
+        })
+        .collect()
 }
 
 fn get_app_icon(app_path: &str) -> Option<Vec<u8>> {
@@ -238,6 +217,20 @@ pub fn ensure_default_browser() -> bool {
     }
 }
 
+pub fn get_current_modifiers() -> Modifiers {
+    const SHIFT: u64 = 1 << 17;
+    const CONTROL: u64 = 1 << 18;
+    const OPTION: u64 = 1 << 19;
+    const COMMAND: u64 = 1 << 20;
+    let flags: u64 = unsafe { msg_send![class!(NSEvent), modifierFlags] };
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, flags & SHIFT != 0);
+    modifiers.set(Modifiers::CTRL, flags & CONTROL != 0);
+    modifiers.set(Modifiers::ALT, flags & OPTION != 0);
+    modifiers.set(Modifiers::LOGO, flags & COMMAND != 0);
+    modifiers
+}
+
 pub fn get_mouse_position() -> Point {
     unsafe {
         let point: NSPoint = msg_send![class!(NSEvent), mouseLocation];
@@ -259,26 +252,112 @@ pub fn get_mouse_position() -> Point {
     }
 }
 
-pub fn open_url(url: String, browser_path: String, profile: Option<String>) {
-    if let Some(profile) = profile {
-        std::process::Command::new("open")
-            .arg("-na")
-            .arg(browser_path.clone())
-            .arg("--args")
-            .arg(format!("--profile-directory={}", profile))
-            .arg(url.clone())
-            .spawn()
-            .unwrap();
-    } else {
-        std::process::Command::new("open")
-            .arg("-na")
-            .arg(browser_path.clone())
-            .arg(url.clone())
-            .spawn()
-            .unwrap();
+pub fn open_url(
+    url: String,
+    browser_path: String,
+    bundle_id: String,
+    profile: Option<String>,
+    private: bool,
+    launch_command: Option<String>,
+) {
+    let options = LaunchOptions {
+        url: &url,
+        profile: profile.as_deref(),
+        private,
+        new_window: false,
+    };
+    if let Some(launch_command) = launch_command {
+        if private {
+            warn!(
+                "no private window mode for custom command {}, opening it normally",
+                bundle_id
+            );
+        }
+        match launcher::expand_command(&launch_command, &options) {
+            Some(command) => spawn_command(&command),
+            None => warn!("invalid launch command: {}", launch_command),
+        }
+        return;
+    }
+    if let Some(app_link) = app_links::find_by_bundle_id(&bundle_id) {
+        if let Some(app_url) = app_link.translate(&url) {
+            spawn_command(&["open".to_string(), "-a".to_string(), browser_path, app_url]);
+            return;
+        }
+    }
+    let launcher = launcher::for_bundle_id(&bundle_id);
+    if private && !launcher.supports_private() {
+        warn!(
+            "no private window mode for {}, opening a normal window",
+            bundle_id
+        );
+    }
+    spawn_command(&launcher.command(&browser_path, &options));
+}
+
+fn spawn_command(command: &[String]) {
+    if let Err(err) = std::process::Command::new(&command[0])
+        .args(&command[1..])
+        .spawn()
+    {
+        warn!("failed to run {}: {}", command[0], err);
+    }
+}
+
+pub fn get_app_path(bundle_id: &str) -> Option<String> {
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace == nil {
+            return None;
+        }
+        let ns_bundle_id = NSString::alloc(nil).init_str(bundle_id);
+        let url: id = msg_send![workspace, URLForApplicationWithBundleIdentifier: ns_bundle_id];
+        if url == nil {
+            return None;
+        }
+        let path: id = msg_send![url, path];
+        if path == nil {
+            return None;
+        }
+        let path_str: id = msg_send![path, UTF8String];
+        if path_str.is_null() {
+            return None;
+        }
+        std::ffi::CStr::from_ptr(path_str as *const _)
+            .to_str()
+            .ok()
+            .map(|s| s.to_owned())
     }
 }
 
+pub fn get_frontmost_app_name() -> Option<String> {
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace == nil {
+            return None;
+        }
+        let app: id = msg_send![workspace, frontmostApplication];
+        if app == nil {
+            return None;
+        }
+        let name: id = msg_send![app, localizedName];
+        if name == nil {
+            return None;
+        }
+        let name_str: id = msg_send![name, UTF8String];
+        if name_str.is_null() {
+            return None;
+        }
+        std::ffi::CStr::from_ptr(name_str as *const _)
+            .to_str()
+            .ok()
+            .map(|s| s.to_owned())
+    }
+}
+
+pub fn get_bundle_identifier(app_path: &str) -> Option<String> {
+    AppBundle::read(Path::new(app_path)).and_then(|bundle| bundle.bundle_id)
+}
 
 #[link(name = "CoreServices", kind = "framework")]
 extern "C" {
@@ -0,0 +1,147 @@
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::get_data_dir;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChromiumProfile {
+    pub directory: String,
+    pub name: String,
+    pub email: Option<String>,
+}
+
+const USER_DATA_DIRS: [(&str, &str); 13] = [
+    ("com.google.Chrome.beta", "Google/Chrome Beta"),
+    ("com.google.Chrome.dev", "Google/Chrome Dev"),
+    ("com.google.Chrome.canary", "Google/Chrome Canary"),
+    ("com.google.Chrome", "Google/Chrome"),
+    ("org.chromium.Chromium", "Chromium"),
+    ("com.microsoft.edgemac.Beta", "Microsoft Edge Beta"),
+    ("com.microsoft.edgemac.Dev", "Microsoft Edge Dev"),
+    ("com.microsoft.edgemac.Canary", "Microsoft Edge Canary"),
+    ("com.microsoft.edgemac", "Microsoft Edge"),
+    ("com.brave.Browser.beta", "BraveSoftware/Brave-Browser-Beta"),
+    (
+        "com.brave.Browser.nightly",
+        "BraveSoftware/Brave-Browser-Nightly",
+    ),
+    ("com.brave.Browser", "BraveSoftware/Brave-Browser"),
+    ("com.vivaldi.Vivaldi", "Vivaldi"),
+];
+
+pub fn local_state_path(bundle_id: &str) -> Option<PathBuf> {
+    let user_data_dir = USER_DATA_DIRS
+        .iter()
+        .find(|(prefix, _)| bundle_id.starts_with(prefix))
+        .map(|(_, user_data_dir)| *user_data_dir)?;
+    Some(
+        Path::new(&get_data_dir())
+            .join("Library/Application Support")
+            .join(user_data_dir)
+            .join("Local State"),
+    )
+}
+
+pub fn discover(bundle_id: &str) -> Vec<ChromiumProfile> {
+    local_state_path(bundle_id)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| parse_local_state(&content))
+        .unwrap_or_default()
+}
+
+pub fn parse_local_state(content: &str) -> Vec<ChromiumProfile> {
+    let Ok(local_state) = serde_json::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+    let Some(info_cache) = local_state["profile"]["info_cache"].as_object() else {
+        return Vec::new();
+    };
+    let non_empty = |value: &Value| {
+        value
+            .as_str()
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    };
+    let mut profiles: Vec<ChromiumProfile> = info_cache
+        .iter()
+        .filter(|(_, info)| info["is_ephemeral"].as_bool() != Some(true))
+        .map(|(directory, info)| ChromiumProfile {
+            directory: directory.clone(),
+            name: non_empty(&info["name"])
+                .or_else(|| non_empty(&info["gaia_name"]))
+                .unwrap_or(directory.clone()),
+            email: non_empty(&info["user_name"]),
+        })
+        .collect();
+    profiles.sort_by_key(|profile| {
+        (
+            profile.directory != "Default",
+            profile.directory.len(),
+            profile.directory.clone(),
+        )
+    });
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCAL_STATE: &str = r#"{
+        "browser": { "enabled_labs_experiments": [] },
+        "profile": {
+            "info_cache": {
+                "Profile 10": { "name": "Side Project", "user_name": "" },
+                "Profile 3": {
+                    "name": "Work",
+                    "gaia_name": "Jane Doe",
+                    "user_name": "jane@corp.example"
+                },
+                "Default": { "name": "", "gaia_name": "Jane", "user_name": "jane@example.com" },
+                "Guest Profile": { "name": "Guest", "is_ephemeral": true }
+            },
+            "last_used": "Profile 3"
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_local_state() {
+        let profiles = parse_local_state(LOCAL_STATE);
+        assert_eq!(
+            profiles,
+            vec![
+                ChromiumProfile {
+                    directory: "Default".to_string(),
+                    name: "Jane".to_string(),
+                    email: Some("jane@example.com".to_string()),
+                },
+                ChromiumProfile {
+                    directory: "Profile 3".to_string(),
+                    name: "Work".to_string(),
+                    email: Some("jane@corp.example".to_string()),
+                },
+                ChromiumProfile {
+                    directory: "Profile 10".to_string(),
+                    name: "Side Project".to_string(),
+                    email: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_local_state() {
+        assert!(parse_local_state("").is_empty());
+        assert!(parse_local_state("{\"profile\": {}}").is_empty());
+    }
+
+    #[test]
+    fn test_local_state_path() {
+        let path = local_state_path("com.google.Chrome.beta").unwrap();
+        assert!(path.ends_with("Library/Application Support/Google/Chrome Beta/Local State"));
+        let path = local_state_path("com.brave.Browser").unwrap();
+        assert!(path.ends_with("BraveSoftware/Brave-Browser/Local State"));
+        assert!(local_state_path("org.mozilla.firefox").is_none());
+    }
+}
@@ -0,0 +1,197 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::get_data_dir;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirefoxProfile {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_default: bool,
+}
+
+pub fn profiles_ini_path(bundle_id: &str) -> Option<PathBuf> {
+    let data_dir = [
+        ("org.mozilla.", "Firefox"),
+        ("io.gitlab.librewolf-community", "librewolf"),
+        ("net.waterfox.waterfox", "Waterfox"),
+        ("app.zen-browser.zen", "zen"),
+        ("org.torproject.torbrowser", "TorBrowser-Data/Browser"),
+    ]
+    .iter()
+    .find(|(prefix, _)| bundle_id.starts_with(prefix))
+    .map(|(_, data_dir)| *data_dir)?;
+    Some(
+        Path::new(&get_data_dir())
+            .join("Library/Application Support")
+            .join(data_dir)
+            .join("profiles.ini"),
+    )
+}
+
+pub fn discover(bundle_id: &str) -> Vec<FirefoxProfile> {
+    profiles_ini_path(bundle_id)
+        .map(|path| read_profiles_ini(&path))
+        .unwrap_or_default()
+}
+
+pub fn read_profiles_ini(path: &Path) -> Vec<FirefoxProfile> {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_profiles_ini(&content, path.parent().unwrap_or(Path::new(""))),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub fn parse_profiles_ini(content: &str, base_dir: &Path) -> Vec<FirefoxProfile> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((section.to_string(), Vec::new()));
+        } else if let (Some((key, value)), Some((_, entries))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let get = |entries: &[(String, String)], key: &str| {
+        entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+    let install_defaults: Vec<String> = sections
+        .iter()
+        .filter(|(section, _)| section.starts_with("Install"))
+        .filter_map(|(_, entries)| get(entries, "Default"))
+        .collect();
+
+    sections
+        .iter()
+        .filter(|(section, _)| section.starts_with("Profile"))
+        .filter_map(|(_, entries)| {
+            let name = get(entries, "Name")?;
+            let raw_path = get(entries, "Path")?;
+            let path = if get(entries, "IsRelative").as_deref() == Some("1") {
+                base_dir.join(&raw_path)
+            } else {
+                PathBuf::from(&raw_path)
+            };
+            let is_default = get(entries, "Default").as_deref() == Some("1")
+                || install_defaults.contains(&raw_path);
+            Some(FirefoxProfile {
+                name,
+                path,
+                is_default,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_PROFILES_INI: &str = "
+[General]
+StartWithLastProfile=1
+
+[Profile0]
+Name=default
+IsRelative=1
+Path=Profiles/abcd1234.default
+Default=1
+
+[Profile1]
+Name=Work
+IsRelative=0
+Path=/Users/me/firefox-work
+";
+
+    const INSTALL_PROFILES_INI: &str = "
+[Install4F96D1932A9F858E]
+Default=Profiles/x9y8z7.default-release
+Locked=1
+
+[Profile1]
+Name=default
+IsRelative=1
+Path=Profiles/abcd1234.default
+Default=1
+
+[Profile0]
+Name=default-release
+IsRelative=1
+Path=Profiles/x9y8z7.default-release
+
+[Profile2]
+Name=Side Project
+IsRelative=1
+Path=Profiles/qwerty.Side Project
+
+[General]
+StartWithLastProfile=1
+Version=2
+";
+
+    #[test]
+    fn test_parse_legacy_profiles_ini() {
+        let profiles = parse_profiles_ini(LEGACY_PROFILES_INI, Path::new("/ff"));
+        assert_eq!(
+            profiles,
+            vec![
+                FirefoxProfile {
+                    name: "default".to_string(),
+                    path: PathBuf::from("/ff/Profiles/abcd1234.default"),
+                    is_default: true,
+                },
+                FirefoxProfile {
+                    name: "Work".to_string(),
+                    path: PathBuf::from("/Users/me/firefox-work"),
+                    is_default: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_install_profiles_ini() {
+        let profiles = parse_profiles_ini(INSTALL_PROFILES_INI, Path::new("/ff"));
+        assert_eq!(profiles.len(), 3);
+        assert_eq!(profiles[1].name, "default-release");
+        assert!(profiles[1].is_default);
+        assert!(profiles[0].is_default);
+        assert_eq!(profiles[2].name, "Side Project");
+        assert_eq!(
+            profiles[2].path,
+            PathBuf::from("/ff/Profiles/qwerty.Side Project")
+        );
+        assert!(!profiles[2].is_default);
+    }
+
+    #[test]
+    fn test_read_profiles_ini() {
+        let dir = std::env::temp_dir().join("gomi-firefox-profiles-test");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("profiles.ini");
+        fs::write(&path, LEGACY_PROFILES_INI).unwrap();
+        let profiles = read_profiles_ini(&path);
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].path, dir.join("Profiles/abcd1234.default"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(read_profiles_ini(&dir.join("missing.ini")).is_empty());
+        assert!(parse_profiles_ini("", Path::new("/")).is_empty());
+    }
+
+    #[test]
+    fn test_profiles_ini_path() {
+        let path = profiles_ini_path("org.mozilla.firefox").unwrap();
+        assert!(path.ends_with("Library/Application Support/Firefox/profiles.ini"));
+        assert!(profiles_ini_path("com.google.Chrome").is_none());
+    }
+}
@@ -0,0 +1,35 @@
+pub mod chromium;
+pub mod firefox;
+
+use crate::launcher::{self, BrowserFamily};
+use crate::storage::BrowserProfile;
+
+pub fn discover(browser_path: &str, bundle_id: &str) -> Vec<BrowserProfile> {
+    match launcher::family(bundle_id) {
+        BrowserFamily::Chromium => chromium::discover(bundle_id)
+            .into_iter()
+            .map(|profile| BrowserProfile {
+                bundle_id: bundle_id.to_string(),
+                browser_path: browser_path.to_string(),
+                profile: profile.directory,
+                description: Some(profile.name),
+                account: profile.email,
+            })
+            .collect(),
+        BrowserFamily::Firefox => {
+            let mut profiles = firefox::discover(bundle_id);
+            profiles.sort_by_key(|profile| !profile.is_default);
+            profiles
+                .into_iter()
+                .map(|profile| BrowserProfile {
+                    bundle_id: bundle_id.to_string(),
+                    browser_path: browser_path.to_string(),
+                    profile: profile.name,
+                    description: None,
+                    account: None,
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+use url::Url;
+
+use crate::domain::registrable_domain;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveScope {
+    ExactUrl,
+    PathPrefix,
+    Host,
+    Domain,
+    Custom,
+}
+
+impl SaveScope {
+    pub const ALL: [SaveScope; 5] = [
+        SaveScope::ExactUrl,
+        SaveScope::PathPrefix,
+        SaveScope::Host,
+        SaveScope::Domain,
+        SaveScope::Custom,
+    ];
+
+    pub fn available(url: &str) -> Vec<SaveScope> {
+        SaveScope::ALL
+            .into_iter()
+            .filter(|scope| *scope == SaveScope::Custom || scope.rule(url).is_some())
+            .collect()
+    }
+
+    pub fn rule(&self, url: &str) -> Option<(&'static str, String)> {
+        let parsed = Url::parse(url).ok();
+        let host = parsed
+            .as_ref()
+            .and_then(|url| url.host_str())
+            .map(|host| host.to_lowercase());
+        match self {
+            SaveScope::ExactUrl => Some(("Equal", url.to_string())),
+            SaveScope::PathPrefix => path_prefix(parsed.as_ref()?).map(|prefix| ("Prefix", prefix)),
+            SaveScope::Host => host.map(|host| ("Host", host)),
+            SaveScope::Domain => registrable_domain(&host?).map(|domain| ("Domain", domain)),
+            SaveScope::Custom => None,
+        }
+    }
+
+    pub fn preview(&self, url: &str) -> String {
+        match (self, self.rule(url)) {
+            (SaveScope::ExactUrl, _) => "Only this exact URL".to_string(),
+            (SaveScope::PathPrefix, Some((_, prefix))) => format!("URLs starting with {}", prefix),
+            (SaveScope::Host, Some((_, host))) => format!("Every page on {}", host),
+            (SaveScope::Domain, Some((_, domain))) => {
+                format!("Every page on {} and its subdomains", domain)
+            }
+            _ => "URLs containing text you choose".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SaveScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveScope::ExactUrl => write!(f, "URL"),
+            SaveScope::PathPrefix => write!(f, "Path"),
+            SaveScope::Host => write!(f, "Host"),
+            SaveScope::Domain => write!(f, "Domain"),
+            SaveScope::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+fn path_prefix(url: &Url) -> Option<String> {
+    url.host_str()?;
+    let path = url.path();
+    let directory = &path[..path.rfind('/')? + 1];
+    if directory == "/" {
+        return None;
+    }
+    let mut prefix = url.clone();
+    prefix.set_path(directory);
+    prefix.set_query(None);
+    prefix.set_fragment(None);
+    Some(prefix.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let url = "https://Gist.GitHub.com/lighthx/abc123/raw?token=1#L3";
+        assert_eq!(
+            SaveScope::ExactUrl.rule(url),
+            Some(("Equal", url.to_string()))
+        );
+        assert_eq!(
+            SaveScope::PathPrefix.rule(url),
+            Some(("Prefix", "https://gist.github.com/lighthx/abc123/".to_string()))
+        );
+        assert_eq!(
+            SaveScope::Host.rule(url),
+            Some(("Host", "gist.github.com".to_string()))
+        );
+        assert_eq!(
+            SaveScope::Domain.rule(url),
+            Some(("Domain", "github.com".to_string()))
+        );
+        assert_eq!(SaveScope::Custom.rule(url), None);
+        assert_eq!(
+            SaveScope::PathPrefix.rule("https://example.com/docs/"),
+            Some(("Prefix", "https://example.com/docs/".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_available() {
+        assert_eq!(
+            SaveScope::available("https://github.com/lighthx/gomi"),
+            SaveScope::ALL.to_vec()
+        );
+        assert_eq!(
+            SaveScope::available("https://github.com/"),
+            vec![
+                SaveScope::ExactUrl,
+                SaveScope::Host,
+                SaveScope::Domain,
+                SaveScope::Custom
+            ]
+        );
+        assert_eq!(
+            SaveScope::available("http://127.0.0.1:8080/api/v1"),
+            vec![
+                SaveScope::ExactUrl,
+                SaveScope::PathPrefix,
+                SaveScope::Host,
+                SaveScope::Custom
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        let url = "https://docs.github.com/en/rest/overview";
+        assert_eq!(
+            SaveScope::PathPrefix.preview(url),
+            "URLs starting with https://docs.github.com/en/rest/"
+        );
+        assert_eq!(SaveScope::Host.preview(url), "Every page on docs.github.com");
+        assert_eq!(
+            SaveScope::Domain.preview(url),
+            "Every page on github.com and its subdomains"
+        );
+        assert_eq!(SaveScope::ExactUrl.preview(url), "Only this exact URL");
+    }
+}
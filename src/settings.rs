@@ -0,0 +1,404 @@
+use iced::keyboard::Modifiers;
+use std::fmt;
+
+use crate::domain::registrable_domain;
+use crate::storage::{PrivacyPolicy, Storage, UrlStorage};
+
+const PRIVATE_MODIFIER_KEY: &str = "private_modifier";
+const SEARCH_PATHS_KEY: &str = "search_paths";
+const READING_LIST_PATH_KEY: &str = "reading_list_path";
+const SCRIPT_COMMAND_KEY: &str = "script_command";
+const INLINE_PROFILES_KEY: &str = "inline_profiles";
+const AUTO_RULE_MODE_KEY: &str = "auto_rule_mode";
+const AUTO_RULE_THRESHOLD_KEY: &str = "auto_rule_threshold";
+const AUTO_RULE_SCOPE_KEY: &str = "auto_rule_scope";
+const BYPASS_MODIFIER_KEY: &str = "bypass_modifier";
+const HISTORY_RETENTION_KEY: &str = "history_retention_days";
+const EXCLUDED_DOMAINS_KEY: &str = "excluded_domains";
+const URL_STORAGE_KEY: &str = "url_storage";
+const HASH_EXACT_RULES_KEY: &str = "hash_exact_rules";
+const DEFAULT_AUTO_RULE_THRESHOLD: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierKey {
+    Alt,
+    Control,
+}
+
+impl ModifierKey {
+    pub const ALL: [ModifierKey; 2] = [ModifierKey::Alt, ModifierKey::Control];
+
+    pub fn is_pressed(&self, modifiers: &Modifiers) -> bool {
+        match self {
+            ModifierKey::Alt => modifiers.alt(),
+            ModifierKey::Control => modifiers.control(),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ModifierKey::Alt => "alt",
+            ModifierKey::Control => "control",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        ModifierKey::ALL
+            .into_iter()
+            .find(|key| key.as_str() == value)
+    }
+}
+
+impl fmt::Display for ModifierKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModifierKey::Alt => write!(f, "Option"),
+            ModifierKey::Control => write!(f, "Control"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BypassModifier {
+    Off,
+    Alt,
+    Control,
+    Shift,
+    Command,
+}
+
+impl BypassModifier {
+    pub const ALL: [BypassModifier; 5] = [
+        BypassModifier::Off,
+        BypassModifier::Alt,
+        BypassModifier::Control,
+        BypassModifier::Shift,
+        BypassModifier::Command,
+    ];
+
+    pub fn is_pressed(&self, modifiers: &Modifiers) -> bool {
+        match self {
+            BypassModifier::Off => false,
+            BypassModifier::Alt => modifiers.alt(),
+            BypassModifier::Control => modifiers.control(),
+            BypassModifier::Shift => modifiers.shift(),
+            BypassModifier::Command => modifiers.logo(),
+        }
+    }
+
+    pub fn conflicts_with(&self, private_modifier: ModifierKey) -> bool {
+        matches!(
+            (self, private_modifier),
+            (BypassModifier::Alt, ModifierKey::Alt)
+                | (BypassModifier::Control, ModifierKey::Control)
+        )
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            BypassModifier::Off => "off",
+            BypassModifier::Alt => "alt",
+            BypassModifier::Control => "control",
+            BypassModifier::Shift => "shift",
+            BypassModifier::Command => "command",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        BypassModifier::ALL
+            .into_iter()
+            .find(|key| key.as_str() == value)
+    }
+}
+
+impl fmt::Display for BypassModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BypassModifier::Off => write!(f, "Off"),
+            BypassModifier::Alt => write!(f, "Option"),
+            BypassModifier::Control => write!(f, "Control"),
+            BypassModifier::Shift => write!(f, "Shift"),
+            BypassModifier::Command => write!(f, "Command"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoRuleMode {
+    Off,
+    Offer,
+    Create,
+}
+
+impl AutoRuleMode {
+    pub const ALL: [AutoRuleMode; 3] =
+        [AutoRuleMode::Off, AutoRuleMode::Offer, AutoRuleMode::Create];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            AutoRuleMode::Off => "off",
+            AutoRuleMode::Offer => "offer",
+            AutoRuleMode::Create => "create",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        AutoRuleMode::ALL
+            .into_iter()
+            .find(|mode| mode.as_str() == value)
+    }
+}
+
+impl fmt::Display for AutoRuleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutoRuleMode::Off => write!(f, "Off"),
+            AutoRuleMode::Offer => write!(f, "Ask first"),
+            AutoRuleMode::Create => write!(f, "Create automatically"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleScope {
+    Host,
+    Domain,
+}
+
+impl RuleScope {
+    pub const ALL: [RuleScope; 2] = [RuleScope::Host, RuleScope::Domain];
+
+    pub fn match_type(&self) -> &'static str {
+        match self {
+            RuleScope::Host => "Host",
+            RuleScope::Domain => "Domain",
+        }
+    }
+
+    pub fn match_value(&self, host: &str) -> String {
+        match self {
+            RuleScope::Host => host.to_string(),
+            RuleScope::Domain => registrable_domain(host).unwrap_or_else(|| host.to_string()),
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        RuleScope::ALL
+            .into_iter()
+            .find(|scope| scope.match_type() == value)
+    }
+}
+
+impl fmt::Display for RuleScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleScope::Host => write!(f, "Exact host"),
+            RuleScope::Domain => write!(f, "Registrable domain"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retention(pub u32);
+
+impl Retention {
+    pub const ALL: [Retention; 6] = [
+        Retention(0),
+        Retention(1),
+        Retention(7),
+        Retention(30),
+        Retention(90),
+        Retention(365),
+    ];
+}
+
+impl fmt::Display for Retention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => write!(f, "Forever"),
+            1 => write!(f, "1 day"),
+            365 => write!(f, "1 year"),
+            days => write!(f, "{} days", days),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub private_modifier: ModifierKey,
+    pub bypass_modifier: BypassModifier,
+    pub search_paths: Vec<String>,
+    pub reading_list_path: String,
+    pub script_command: String,
+    pub inline_profiles: bool,
+    pub auto_rule_mode: AutoRuleMode,
+    pub auto_rule_threshold: u32,
+    pub auto_rule_scope: RuleScope,
+    pub history_retention: Retention,
+    pub excluded_domains: Vec<String>,
+    pub url_storage: UrlStorage,
+    pub hash_exact_rules: bool,
+}
+
+impl Settings {
+    pub fn load(storage: &Storage) -> Self {
+        let private_modifier = storage
+            .get_setting(PRIVATE_MODIFIER_KEY)
+            .and_then(|value| ModifierKey::parse(&value))
+            .unwrap_or(ModifierKey::Alt);
+        Settings {
+            private_modifier,
+            bypass_modifier: storage
+                .get_setting(BYPASS_MODIFIER_KEY)
+                .and_then(|value| BypassModifier::parse(&value))
+                .filter(|key| !key.conflicts_with(private_modifier))
+                .unwrap_or(BypassModifier::Shift),
+            search_paths: storage
+                .get_setting(SEARCH_PATHS_KEY)
+                .and_then(|value| serde_json::from_str(&value).ok())
+                .unwrap_or_default(),
+            reading_list_path: storage
+                .get_setting(READING_LIST_PATH_KEY)
+                .unwrap_or_default(),
+            script_command: storage.get_setting(SCRIPT_COMMAND_KEY).unwrap_or_default(),
+            inline_profiles: storage.get_setting(INLINE_PROFILES_KEY).as_deref() == Some("true"),
+            auto_rule_mode: storage
+                .get_setting(AUTO_RULE_MODE_KEY)
+                .and_then(|value| AutoRuleMode::parse(&value))
+                .unwrap_or(AutoRuleMode::Offer),
+            auto_rule_threshold: storage
+                .get_setting(AUTO_RULE_THRESHOLD_KEY)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_AUTO_RULE_THRESHOLD),
+            auto_rule_scope: storage
+                .get_setting(AUTO_RULE_SCOPE_KEY)
+                .and_then(|value| RuleScope::parse(&value))
+                .unwrap_or(RuleScope::Host),
+            history_retention: Retention(
+                storage
+                    .get_setting(HISTORY_RETENTION_KEY)
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(0),
+            ),
+            excluded_domains: storage
+                .get_setting(EXCLUDED_DOMAINS_KEY)
+                .and_then(|value| serde_json::from_str(&value).ok())
+                .unwrap_or_default(),
+            url_storage: storage
+                .get_setting(URL_STORAGE_KEY)
+                .and_then(|value| UrlStorage::parse(&value))
+                .unwrap_or_default(),
+            hash_exact_rules: storage.get_setting(HASH_EXACT_RULES_KEY).as_deref() == Some("true"),
+        }
+    }
+
+    pub fn privacy_policy(&self) -> PrivacyPolicy {
+        PrivacyPolicy {
+            retention_days: self.history_retention.0,
+            excluded_domains: self.excluded_domains.clone(),
+            url_storage: self.url_storage,
+            hash_exact_rules: self.hash_exact_rules,
+        }
+    }
+
+    pub fn set_private_modifier(&mut self, storage: &Storage, key: ModifierKey) {
+        if self.bypass_modifier.conflicts_with(key) {
+            return;
+        }
+        self.private_modifier = key;
+        storage.set_setting(PRIVATE_MODIFIER_KEY, key.as_str());
+    }
+
+    pub fn set_bypass_modifier(&mut self, storage: &Storage, key: BypassModifier) {
+        if key.conflicts_with(self.private_modifier) {
+            return;
+        }
+        self.bypass_modifier = key;
+        storage.set_setting(BYPASS_MODIFIER_KEY, key.as_str());
+    }
+
+    pub fn set_reading_list_path(&mut self, storage: &Storage, path: String) {
+        storage.set_setting(READING_LIST_PATH_KEY, &path);
+        self.reading_list_path = path;
+    }
+
+    pub fn set_script_command(&mut self, storage: &Storage, command: String) {
+        storage.set_setting(SCRIPT_COMMAND_KEY, &command);
+        self.script_command = command;
+    }
+
+    pub fn set_inline_profiles(&mut self, storage: &Storage, inline_profiles: bool) {
+        self.inline_profiles = inline_profiles;
+        storage.set_setting(INLINE_PROFILES_KEY, &inline_profiles.to_string());
+    }
+
+    pub fn set_auto_rule_mode(&mut self, storage: &Storage, mode: AutoRuleMode) {
+        self.auto_rule_mode = mode;
+        storage.set_setting(AUTO_RULE_MODE_KEY, mode.as_str());
+    }
+
+    pub fn set_auto_rule_threshold(&mut self, storage: &Storage, threshold: u32) {
+        self.auto_rule_threshold = threshold;
+        storage.set_setting(AUTO_RULE_THRESHOLD_KEY, &threshold.to_string());
+    }
+
+    pub fn set_auto_rule_scope(&mut self, storage: &Storage, scope: RuleScope) {
+        self.auto_rule_scope = scope;
+        storage.set_setting(AUTO_RULE_SCOPE_KEY, scope.match_type());
+    }
+
+    pub fn set_history_retention(&mut self, storage: &Storage, retention: Retention) {
+        self.history_retention = retention;
+        storage.set_setting(HISTORY_RETENTION_KEY, &retention.0.to_string());
+    }
+
+    pub fn set_url_storage(&mut self, storage: &Storage, url_storage: UrlStorage) {
+        self.url_storage = url_storage;
+        storage.set_setting(URL_STORAGE_KEY, url_storage.as_str());
+    }
+
+    pub fn set_hash_exact_rules(&mut self, storage: &Storage, hash_exact_rules: bool) {
+        self.hash_exact_rules = hash_exact_rules;
+        storage.set_setting(HASH_EXACT_RULES_KEY, &hash_exact_rules.to_string());
+    }
+
+    pub fn add_excluded_domain(&mut self, storage: &Storage, domain: String) {
+        let domain = domain.trim().trim_start_matches("*.").to_lowercase();
+        if !domain.is_empty() && !self.excluded_domains.contains(&domain) {
+            self.excluded_domains.push(domain);
+            self.save_excluded_domains(storage);
+        }
+    }
+
+    pub fn remove_excluded_domain(&mut self, storage: &Storage, domain: &str) {
+        self.excluded_domains
+            .retain(|excluded_domain| excluded_domain != domain);
+        self.save_excluded_domains(storage);
+    }
+
+    fn save_excluded_domains(&self, storage: &Storage) {
+        storage.set_setting(
+            EXCLUDED_DOMAINS_KEY,
+            &serde_json::to_string(&self.excluded_domains).unwrap(),
+        );
+    }
+
+    pub fn add_search_path(&mut self, storage: &Storage, path: String) {
+        if !self.search_paths.contains(&path) {
+            self.search_paths.push(path);
+            self.save_search_paths(storage);
+        }
+    }
+
+    pub fn remove_search_path(&mut self, storage: &Storage, path: &str) {
+        self.search_paths.retain(|search_path| search_path != path);
+        self.save_search_paths(storage);
+    }
+
+    fn save_search_paths(&self, storage: &Storage) {
+        storage.set_setting(
+            SEARCH_PATHS_KEY,
+            &serde_json::to_string(&self.search_paths).unwrap(),
+        );
+    }
+}
@@ -1,203 +1,1153 @@
+use crate::actions::ACTION_PREFIX;
 use crate::config::get_db_path;
-use rusqlite::Connection;
+use crate::domain::{host_matches_domain, url_host};
+use rusqlite::{Connection, Row};
+use sha2::{Digest, Sha256};
+use std::fmt;
 use std::sync::{Arc, Mutex};
 #[derive(Debug, Clone)]
 pub struct Storage {
     connection: Arc<Mutex<Connection>>,
+    privacy: Arc<Mutex<PrivacyPolicy>>,
+    url_hash_salt: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BrowserInfo {
+    pub bundle_id: String,
     pub name: String,
     pub path: String,
     pub icon_data: Vec<u8>,
+    pub launch_command: Option<String>,
+    pub hidden: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MatchItem {
+    pub bundle_id: String,
     pub browser_path: String,
     pub profile: Option<String>,
     pub match_type: String,
     pub match_value: String,
+    pub private: bool,
+    pub auto_created: bool,
+}
+
+impl MatchItem {
+    pub fn precedence(&self) -> u8 {
+        match_precedence(&self.match_type)
+    }
+
+    pub fn is_hashed(&self) -> bool {
+        self.match_type == "Equal" && self.match_value.starts_with(URL_HASH_PREFIX)
+    }
+}
+
+pub fn match_precedence(match_type: &str) -> u8 {
+    match match_type {
+        "Equal" => 0,
+        "Prefix" => 1,
+        "Contain" => 2,
+        "Host" => 3,
+        _ => 4,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BrowserProfile {
+    pub bundle_id: String,
     pub browser_path: String,
     pub profile: String,
     pub description: Option<String>,
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub url: String,
+    pub opened_at: u64,
+    pub source: Option<String>,
+    pub match_type: Option<String>,
+    pub match_value: Option<String>,
+    pub bundle_id: String,
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UrlStorage {
+    #[default]
+    FullUrl,
+    HostOnly,
+}
+
+impl UrlStorage {
+    pub const ALL: [UrlStorage; 2] = [UrlStorage::FullUrl, UrlStorage::HostOnly];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UrlStorage::FullUrl => "full_url",
+            UrlStorage::HostOnly => "host_only",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        UrlStorage::ALL
+            .into_iter()
+            .find(|storage| storage.as_str() == value)
+    }
 }
 
+impl fmt::Display for UrlStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UrlStorage::FullUrl => write!(f, "Full URL"),
+            UrlStorage::HostOnly => write!(f, "Host only"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PrivacyPolicy {
+    pub retention_days: u32,
+    pub excluded_domains: Vec<String>,
+    pub url_storage: UrlStorage,
+    pub hash_exact_rules: bool,
+}
+
+impl PrivacyPolicy {
+    fn is_excluded(&self, host: &str) -> bool {
+        self.excluded_domains
+            .iter()
+            .any(|domain| host_matches_domain(host, domain))
+    }
+
+    fn cutoff(&self, now: u64) -> Option<u64> {
+        (self.retention_days > 0)
+            .then(|| now.saturating_sub(self.retention_days as u64 * 24 * 60 * 60))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostStreak {
+    pub bundle_id: String,
+    pub profile: Option<String>,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostChoice {
+    pub bundle_id: String,
+    pub profile: Option<String>,
+    pub score: f64,
+}
+
+const BROWSER_COLUMNS: &str =
+    "bundle_id, coalesce(display_name, name), path, icon_data, launch_command, hidden";
+const BROWSER_ORDER: &str =
+    "ORDER BY sort_order IS NULL, sort_order, coalesce(display_name, name) COLLATE NOCASE";
+const MATCH_COLUMNS: &str =
+    "bundle_id, browser_path, profile, match_type, match_value, private, auto_created";
+const PROFILE_COLUMNS: &str = "bundle_id, browser_path, profile, description, account";
+const HISTORY_COLUMNS: &str =
+    "history.id, history.url, opened_at, history.source, match_type, match_value, bundle_id, profile";
+const HISTORY_LIMIT: u32 = 500;
+const CHOICE_HALF_LIFE_SECS: u64 = 30 * 24 * 60 * 60;
+const MIN_CHOICE_SCORE: f64 = 0.01;
+const URL_HASH_SALT_KEY: &str = "url_hash_salt";
+const URL_HASH_PREFIX: &str = "sha256:";
+
 impl Storage {
     pub fn new() -> Self {
-        let connection = Connection::open(get_db_path()).unwrap();
+        Self::open(&get_db_path())
+    }
+
+    pub fn open(db_path: &str) -> Self {
+        let connection = Connection::open(db_path).unwrap();
+        if has_table(&connection, "browsers") && !has_column(&connection, "browsers", "bundle_id") {
+            connection.execute("DROP TABLE browsers", ()).unwrap();
+        }
+        let stale_history_index =
+            has_table(&connection, "history") && !has_trigger(&connection, "history_fts_update");
         connection.execute_batch("
         BEGIN;
-         CREATE TABLE IF NOT EXISTS browsers (path text primary key, name text not null, icon_data blob not null);
-         CREATE TABLE IF NOT EXISTS browser_profiles (browser_path text not null, profile text not null, description text, primary key(browser_path, profile));
-         CREATE TABLE IF NOT EXISTS matches (browser_path text not null, profile text, match_type text not null, match_value text primary key);
+         CREATE TABLE IF NOT EXISTS browsers (bundle_id text primary key, path text not null, name text not null, icon_data blob not null);
+         CREATE TABLE IF NOT EXISTS browser_profiles (bundle_id text not null, browser_path text not null, profile text not null, description text, account text, primary key(bundle_id, profile));
+         CREATE TABLE IF NOT EXISTS matches (bundle_id text, browser_path text not null, profile text, match_type text not null, match_value text primary key, private integer not null default 0);
+         CREATE TABLE IF NOT EXISTS settings (key text primary key, value text not null);
+         CREATE TABLE IF NOT EXISTS host_streaks (host text primary key, bundle_id text not null, profile text not null default '', count integer not null);
+         CREATE TABLE IF NOT EXISTS history (id integer primary key autoincrement, url text not null, opened_at integer not null, source text, match_type text, match_value text, bundle_id text not null, profile text);
+         CREATE INDEX IF NOT EXISTS history_opened_at ON history (opened_at);
+         CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(url, source, content='history', content_rowid='id');
+         CREATE TRIGGER IF NOT EXISTS history_fts_insert AFTER INSERT ON history BEGIN
+           INSERT INTO history_fts (rowid, url, source) VALUES (new.id, new.url, new.source);
+         END;
+         CREATE TRIGGER IF NOT EXISTS history_fts_delete AFTER DELETE ON history BEGIN
+           INSERT INTO history_fts (history_fts, rowid, url, source) VALUES ('delete', old.id, old.url, old.source);
+         END;
+         CREATE TRIGGER IF NOT EXISTS history_fts_update AFTER UPDATE ON history BEGIN
+           INSERT INTO history_fts (history_fts, rowid, url, source) VALUES ('delete', old.id, old.url, old.source);
+           INSERT INTO history_fts (rowid, url, source) VALUES (new.id, new.url, new.source);
+         END;
+         CREATE TABLE IF NOT EXISTS host_choices (host text not null, bundle_id text not null, profile text not null default '', score real not null, updated_at integer not null, primary key(host, bundle_id, profile));
+         CREATE TABLE IF NOT EXISTS dismissed_profiles (bundle_id text not null, profile text not null, primary key(bundle_id, profile));
          COMMIT;
          ").unwrap();
+        if stale_history_index {
+            connection
+                .execute(
+                    "INSERT INTO history_fts (history_fts) VALUES ('rebuild')",
+                    (),
+                )
+                .unwrap();
+        }
+        ensure_column(
+            &connection,
+            "matches",
+            "private",
+            "integer not null default 0",
+        );
+        ensure_column(&connection, "matches", "bundle_id", "text");
+        ensure_column(
+            &connection,
+            "matches",
+            "auto_created",
+            "integer not null default 0",
+        );
+        ensure_column(&connection, "browser_profiles", "account", "text");
+        ensure_column(
+            &connection,
+            "browsers",
+            "missing",
+            "integer not null default 0",
+        );
+        ensure_column(&connection, "browsers", "launch_command", "text");
+        ensure_column(
+            &connection,
+            "browsers",
+            "hidden",
+            "integer not null default 0",
+        );
+        ensure_column(&connection, "browsers", "display_name", "text");
+        ensure_column(&connection, "browsers", "sort_order", "integer");
+        ensure_column(
+            &connection,
+            "browsers",
+            "manual",
+            "integer not null default 0",
+        );
+        if !has_column(&connection, "browser_profiles", "bundle_id") {
+            connection.execute_batch("
+            BEGIN;
+             ALTER TABLE browser_profiles RENAME TO legacy_browser_profiles;
+             CREATE TABLE browser_profiles (bundle_id text not null, browser_path text not null, profile text not null, description text, account text, primary key(bundle_id, profile));
+             INSERT INTO browser_profiles (bundle_id, browser_path, profile, description, account) SELECT browser_path, browser_path, profile, description, account FROM legacy_browser_profiles;
+             DROP TABLE legacy_browser_profiles;
+             COMMIT;
+             ").unwrap();
+        }
+        connection
+            .execute(
+                "UPDATE matches SET bundle_id = browser_path WHERE bundle_id IS NULL",
+                (),
+            )
+            .unwrap();
+        let url_hash_salt = url_hash_salt(&connection);
         Storage {
             connection: Arc::new(Mutex::new(connection)),
+            privacy: Arc::new(Mutex::new(PrivacyPolicy::default())),
+            url_hash_salt,
         }
     }
+    pub fn set_privacy_policy(&self, policy: PrivacyPolicy, now: u64) {
+        *self.privacy.lock().unwrap() = policy.clone();
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction().unwrap();
+        if let Some(cutoff) = policy.cutoff(now) {
+            prune_before(&tx, cutoff);
+        }
+        let history: Vec<(i64, String)> = tx
+            .prepare("SELECT id, url FROM history")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        for (id, url) in history {
+            if url_host(&url).is_some_and(|host| policy.is_excluded(&host)) {
+                tx.execute("DELETE FROM history WHERE id = ?", [id])
+                    .unwrap();
+                continue;
+            }
+            let stored_url = self.history_url(&policy, &url);
+            if stored_url != url {
+                tx.execute("UPDATE history SET url = ? WHERE id = ?", (stored_url, id))
+                    .unwrap();
+            }
+        }
+        let hosts: Vec<String> = tx
+            .prepare("SELECT host FROM host_choices UNION SELECT host FROM host_streaks")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        for host in hosts.into_iter().filter(|host| policy.is_excluded(host)) {
+            tx.execute("DELETE FROM host_choices WHERE host = ?", [&host])
+                .unwrap();
+            tx.execute("DELETE FROM host_streaks WHERE host = ?", [&host])
+                .unwrap();
+        }
+        if policy.hash_exact_rules {
+            let equal_items: Vec<MatchItem> = tx
+                .prepare(&format!(
+                    "SELECT {} FROM matches WHERE match_type = 'Equal' AND match_value NOT LIKE '{}%'",
+                    MATCH_COLUMNS, URL_HASH_PREFIX
+                ))
+                .unwrap()
+                .query_map([], match_item_from_row)
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect();
+            for item in equal_items {
+                let stored = self.stored_match(&policy, item.clone());
+                tx.execute(
+                    "UPDATE OR IGNORE matches SET match_value = ? WHERE match_value = ?",
+                    (stored.match_value, &item.match_value),
+                )
+                .unwrap();
+                tx.execute(
+                    "DELETE FROM matches WHERE match_value = ?",
+                    [item.match_value],
+                )
+                .unwrap();
+            }
+        }
+        tx.commit().unwrap();
+    }
+    pub fn purge_history(&self) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute_batch(
+                "DELETE FROM history; DELETE FROM host_choices; DELETE FROM host_streaks;",
+            )
+            .unwrap();
+    }
+    fn url_hash(&self, url: &str) -> String {
+        format!(
+            "{}{}",
+            URL_HASH_PREFIX,
+            hex(&Sha256::digest(format!("{}{}", self.url_hash_salt, url)))
+        )
+    }
+    fn history_url(&self, policy: &PrivacyPolicy, url: &str) -> String {
+        match policy.url_storage {
+            UrlStorage::FullUrl => url.to_string(),
+            UrlStorage::HostOnly => match url::Url::parse(url) {
+                Ok(url) => match (url.host_str(), url.port()) {
+                    (Some(host), Some(port)) => format!("{}://{}:{}", url.scheme(), host, port),
+                    (Some(host), None) => format!("{}://{}", url.scheme(), host),
+                    (None, _) => format!("{}:", url.scheme()),
+                },
+                Err(_) => String::new(),
+            },
+        }
+    }
+    fn stored_match(&self, policy: &PrivacyPolicy, match_item: MatchItem) -> MatchItem {
+        if match_item.match_type != "Equal" || !policy.hash_exact_rules {
+            return match_item;
+        }
+        MatchItem {
+            match_value: self.url_hash(&match_item.match_value),
+            ..match_item
+        }
+    }
+    pub fn resolve_bundle_ids(&self, resolve: impl Fn(&str) -> Option<String>) {
+        let mut connection = self.connection.lock().unwrap();
+        let unresolved: Vec<String> = {
+            let mut stmt = connection
+                .prepare("SELECT bundle_id FROM matches WHERE bundle_id LIKE '/%' UNION SELECT bundle_id FROM browser_profiles WHERE bundle_id LIKE '/%'")
+                .unwrap();
+            let paths = stmt
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect();
+            paths
+        };
+        let tx = connection.transaction().unwrap();
+        for path in unresolved {
+            if let Some(bundle_id) = resolve(&path) {
+                tx.execute(
+                    "UPDATE matches SET bundle_id = ? WHERE bundle_id = ?",
+                    (&bundle_id, &path),
+                )
+                .unwrap();
+                tx.execute(
+                    "UPDATE OR IGNORE browser_profiles SET bundle_id = ? WHERE bundle_id = ?",
+                    (&bundle_id, &path),
+                )
+                .unwrap();
+                tx.execute("DELETE FROM browser_profiles WHERE bundle_id = ?", (&path,))
+                    .unwrap();
+            }
+        }
+        tx.commit().unwrap();
+    }
     pub fn batch_insert_browsers(&mut self, browsers: Vec<BrowserInfo>) {
         let mut connection = self.connection.lock().unwrap();
         let tx = connection.transaction().unwrap();
         {
             let mut stmt = tx
-                .prepare("INSERT INTO browsers (name, path, icon_data) VALUES (?, ?, ?) ON CONFLICT(path) DO NOTHING")
+                .prepare("INSERT INTO browsers (bundle_id, path, name, icon_data) VALUES (?, ?, ?, ?) ON CONFLICT(bundle_id) DO UPDATE SET path = excluded.path, name = excluded.name, icon_data = excluded.icon_data, missing = 0")
+                .unwrap();
+            let mut update_match_paths = tx
+                .prepare("UPDATE matches SET browser_path = ? WHERE bundle_id = ?")
+                .unwrap();
+            let mut update_profile_paths = tx
+                .prepare("UPDATE browser_profiles SET browser_path = ? WHERE bundle_id = ?")
                 .unwrap();
 
             for browser in browsers {
-                stmt.execute((&browser.name, &browser.path, &browser.icon_data))
+                stmt.execute((
+                    &browser.bundle_id,
+                    &browser.path,
+                    &browser.name,
+                    &browser.icon_data,
+                ))
+                .unwrap();
+                update_match_paths
+                    .execute((&browser.path, &browser.bundle_id))
+                    .unwrap();
+                update_profile_paths
+                    .execute((&browser.path, &browser.bundle_id))
                     .unwrap();
             }
         }
         tx.commit().unwrap();
     }
 
-    pub fn delete_all_browsers(&mut self) {
+    pub fn reconcile_browsers(&mut self, browsers: Vec<BrowserInfo>) -> Vec<BrowserInfo> {
+        let installed: Vec<String> = browsers
+            .iter()
+            .map(|browser| browser.bundle_id.clone())
+            .collect();
+        self.batch_insert_browsers(browsers);
+
+        let connection = self.connection.lock().unwrap();
+        let removed: Vec<BrowserInfo> = {
+            let mut stmt = connection
+                .prepare(&format!(
+                    "SELECT {} FROM browsers WHERE missing = 0 AND manual = 0",
+                    BROWSER_COLUMNS
+                ))
+                .unwrap();
+            let browsers = stmt
+                .query_map([], browser_info_from_row)
+                .unwrap()
+                .map(|r| r.unwrap())
+                .filter(|browser| !installed.contains(&browser.bundle_id))
+                .collect();
+            browsers
+        };
+        for browser in &removed {
+            connection
+                .execute(
+                    "UPDATE browsers SET missing = 1 WHERE bundle_id = ?",
+                    [&browser.bundle_id],
+                )
+                .unwrap();
+        }
+        removed
+    }
+
+    pub fn insert_manual_browser(&self, browser: BrowserInfo) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "INSERT INTO browsers (bundle_id, path, name, icon_data, launch_command, manual) VALUES (?, ?, ?, ?, ?, 1) ON CONFLICT(bundle_id) DO UPDATE SET path = excluded.path, name = excluded.name, icon_data = excluded.icon_data, launch_command = excluded.launch_command, manual = 1, missing = 0",
+                (browser.bundle_id, browser.path, browser.name, browser.icon_data, browser.launch_command),
+            )
+            .unwrap();
+    }
+
+    pub fn delete_manual_browser(&self, bundle_id: String) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "DELETE FROM browsers WHERE bundle_id = ? AND manual = 1",
+                [bundle_id],
+            )
+            .unwrap();
+    }
+
+    pub fn is_bundle_id_used(&self, bundle_id: &str) -> bool {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare("SELECT bundle_id FROM browsers WHERE bundle_id = ?1 UNION SELECT bundle_id FROM matches WHERE bundle_id = ?1 UNION SELECT bundle_id FROM browser_profiles WHERE bundle_id = ?1")
+            .unwrap();
+        stmt.exists([bundle_id]).unwrap()
+    }
+
+    pub fn get_manual_browsers(&self) -> Vec<BrowserInfo> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM browsers WHERE manual = 1 {}",
+                BROWSER_COLUMNS, BROWSER_ORDER
+            ))
+            .unwrap();
+        stmt.query_map([], browser_info_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+
+    pub fn set_browser_hidden(&self, bundle_id: String, hidden: bool) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "UPDATE browsers SET hidden = ? WHERE bundle_id = ?",
+                (hidden, bundle_id),
+            )
+            .unwrap();
+    }
+
+    pub fn rename_browser(&self, bundle_id: String, display_name: Option<String>) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "UPDATE browsers SET display_name = ? WHERE bundle_id = ?",
+                (display_name, bundle_id),
+            )
+            .unwrap();
+    }
+
+    pub fn set_browser_order(&mut self, bundle_ids: Vec<String>) {
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction().unwrap();
+        for (sort_order, bundle_id) in bundle_ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE browsers SET sort_order = ? WHERE bundle_id = ?",
+                (sort_order as i64, bundle_id),
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+    }
+
+    pub fn get_browsers(&self) -> Vec<BrowserInfo> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM browsers WHERE missing = 0 {}",
+                BROWSER_COLUMNS, BROWSER_ORDER
+            ))
+            .unwrap();
+        stmt.query_map([], browser_info_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+    pub fn get_missing_browsers(&self) -> Vec<BrowserInfo> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM browsers WHERE missing = 1",
+                BROWSER_COLUMNS
+            ))
+            .unwrap();
+        stmt.query_map([], browser_info_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+    pub fn get_browser(&self, bundle_id: String) -> Option<BrowserInfo> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM browsers WHERE bundle_id = ? AND missing = 0",
+                BROWSER_COLUMNS
+            ))
+            .unwrap();
+        let browser = stmt
+            .query_map([bundle_id], browser_info_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .next();
+        browser
+    }
+    pub fn insert_match(&self, match_item: MatchItem) {
+        let match_item = self.stored_match(&self.privacy.lock().unwrap(), match_item);
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "INSERT INTO matches (bundle_id, browser_path, profile, match_type, match_value, private, auto_created) VALUES (?, ?, ?, ?, ?, ?, ?) ON CONFLICT(match_value) DO NOTHING",
+                (match_item.bundle_id, match_item.browser_path, match_item.profile, match_item.match_type, match_item.match_value, match_item.private, match_item.auto_created),
+            )
+            .unwrap();
+    }
+    pub fn find_equal_matches_by_url(&self, url: String) -> Option<MatchItem> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM matches WHERE match_type = ? AND match_value IN (?, ?)",
+                MATCH_COLUMNS
+            ))
+            .unwrap();
+        let result: Vec<MatchItem> = stmt
+            .query_map(["Equal", &url, &self.url_hash(&url)], match_item_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        if result.is_empty() {
+            None
+        } else {
+            Some(result[0].clone())
+        }
+    }
+    pub fn find_contain_matches_by_url(&self, url: String) -> Option<MatchItem> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM matches WHERE match_type = ?",
+                MATCH_COLUMNS
+            ))
+            .unwrap();
+        let result: Vec<MatchItem> = stmt
+            .query_map(["Contain"], match_item_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        let matched = result.iter().find(|item| url.contains(&item.match_value));
+        matched.cloned()
+    }
+    pub fn find_prefix_matches_by_url(&self, url: String) -> Option<MatchItem> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM matches WHERE match_type = ?",
+                MATCH_COLUMNS
+            ))
+            .unwrap();
+        let result: Vec<MatchItem> = stmt
+            .query_map(["Prefix"], match_item_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        result
+            .into_iter()
+            .filter(|item| url.starts_with(&item.match_value))
+            .max_by_key(|item| item.match_value.len())
+    }
+    pub fn find_rules_matching(&self, url: &str) -> Vec<MatchItem> {
+        let url_hash = self.url_hash(url);
+        let host = url_host(url);
+        let mut rules: Vec<MatchItem> = self
+            .find_all_match_items()
+            .into_iter()
+            .filter(|item| match item.match_type.as_str() {
+                "Equal" => item.match_value == url || item.match_value == url_hash,
+                "Prefix" => url.starts_with(&item.match_value),
+                "Contain" => url.contains(&item.match_value),
+                "Host" => host.as_deref() == Some(item.match_value.as_str()),
+                "Domain" => host
+                    .as_deref()
+                    .is_some_and(|host| host_matches_domain(host, &item.match_value)),
+                _ => false,
+            })
+            .collect();
+        rules.sort_by_key(|item| item.precedence());
+        rules
+    }
+    pub fn find_host_matches(&self, host: String) -> Option<MatchItem> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM matches WHERE match_type IN ('Host', 'Domain') ORDER BY match_type = 'Domain'",
+                MATCH_COLUMNS
+            ))
+            .unwrap();
+        let result: Vec<MatchItem> = stmt
+            .query_map([], match_item_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        result
+            .into_iter()
+            .find(|item| match item.match_type.as_str() {
+                "Host" => item.match_value == host,
+                _ => host_matches_domain(&host, &item.match_value),
+            })
+    }
+    pub fn insert_browser_profile(&self, browser_profile: BrowserProfile) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "DELETE FROM dismissed_profiles WHERE bundle_id = ? AND profile = ?",
+                (&browser_profile.bundle_id, &browser_profile.profile),
+            )
+            .unwrap();
+        connection
+            .execute(
+                "INSERT INTO browser_profiles (bundle_id, browser_path, profile, description, account) VALUES (?, ?, ?, ?, ?) ON CONFLICT(bundle_id, profile) DO UPDATE SET browser_path = excluded.browser_path, description = coalesce(excluded.description, description), account = coalesce(excluded.account, account)",
+                (browser_profile.bundle_id, browser_profile.browser_path, browser_profile.profile, browser_profile.description, browser_profile.account),
+            )
+            .unwrap();
+    }
+    pub fn insert_discovered_profiles(&self, browser_profiles: Vec<BrowserProfile>) {
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction().unwrap();
+        for browser_profile in browser_profiles {
+            tx.execute(
+                "INSERT INTO browser_profiles (bundle_id, browser_path, profile, description, account) SELECT ?1, ?2, ?3, ?4, ?5 WHERE NOT EXISTS (SELECT 1 FROM dismissed_profiles WHERE bundle_id = ?1 AND profile = ?3) ON CONFLICT(bundle_id, profile) DO UPDATE SET browser_path = excluded.browser_path, description = coalesce(excluded.description, description), account = coalesce(excluded.account, account)",
+                (browser_profile.bundle_id, browser_profile.browser_path, browser_profile.profile, browser_profile.description, browser_profile.account),
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+    }
+    pub fn get_browser_profiles(&self, bundle_id: String) -> Vec<BrowserProfile> {
         let connection = self.connection.lock().unwrap();
-        connection.execute("DELETE FROM browsers", ()).unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM browser_profiles WHERE bundle_id = ?",
+                PROFILE_COLUMNS
+            ))
+            .unwrap();
+        stmt.query_map([bundle_id], browser_profile_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+    pub fn find_orphaned_browser_profiles(&self) -> Vec<BrowserProfile> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM browser_profiles WHERE bundle_id NOT IN (SELECT bundle_id FROM browsers WHERE missing = 0)",
+                PROFILE_COLUMNS
+            ))
+            .unwrap();
+        stmt.query_map([], browser_profile_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+    pub fn delete_orphaned_browser_profiles(&self) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "DELETE FROM browser_profiles WHERE bundle_id NOT IN (SELECT bundle_id FROM browsers WHERE missing = 0)",
+                (),
+            )
+            .unwrap();
+    }
+    pub fn delete_browser_profile(&self, bundle_id: String, profile: String) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "DELETE FROM browser_profiles WHERE bundle_id = ? AND profile = ?",
+                (&bundle_id, &profile),
+            )
+            .unwrap();
+        connection
+            .execute(
+                "INSERT OR IGNORE INTO dismissed_profiles (bundle_id, profile) VALUES (?, ?)",
+                (bundle_id, profile),
+            )
+            .unwrap();
+    }
+    pub fn delete_match_by_profile_and_bundle_id(&self, bundle_id: String, profile: String) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "DELETE FROM matches WHERE bundle_id = ? AND profile = ?",
+                (bundle_id, profile),
+            )
+            .unwrap();
+    }
+    pub fn delete_match_by_match_value(&self, match_value: String) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute("DELETE FROM matches WHERE match_value = ?", (match_value,))
+            .unwrap();
+    }
+    pub fn set_match_private(&self, match_value: String, private: bool) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "UPDATE matches SET private = ? WHERE match_value = ?",
+                (private, match_value),
+            )
+            .unwrap();
+    }
+    pub fn find_orphaned_match_items(&self) -> Vec<MatchItem> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT {} FROM matches WHERE bundle_id NOT LIKE '{}%' AND (bundle_id NOT IN (SELECT bundle_id FROM browsers WHERE missing = 0) OR (profile IS NOT NULL AND NOT EXISTS (SELECT 1 FROM browser_profiles WHERE browser_profiles.bundle_id = matches.bundle_id AND browser_profiles.profile = matches.profile)))",
+                MATCH_COLUMNS, ACTION_PREFIX
+            ))
+            .unwrap();
+        stmt.query_map([], match_item_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+    pub fn reassign_matches(
+        &mut self,
+        match_values: Vec<String>,
+        bundle_id: String,
+        browser_path: String,
+        profile: Option<String>,
+    ) {
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction().unwrap();
+        for match_value in match_values {
+            tx.execute(
+                "UPDATE matches SET bundle_id = ?, browser_path = ?, profile = ? WHERE match_value = ?",
+                (&bundle_id, &browser_path, &profile, &match_value),
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+    }
+    pub fn find_all_match_items(&self) -> Vec<MatchItem> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection
+            .prepare(&format!("SELECT {} FROM matches", MATCH_COLUMNS))
+            .unwrap();
+        stmt.query_map([], match_item_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+    pub fn record_host_choice(
+        &self,
+        host: String,
+        bundle_id: String,
+        profile: Option<String>,
+        now: u64,
+    ) -> u32 {
+        let policy = self.privacy.lock().unwrap().clone();
+        if policy.is_excluded(&host) {
+            return 0;
+        }
+        let connection = self.connection.lock().unwrap();
+        if let Some(cutoff) = policy.cutoff(now) {
+            prune_before(&connection, cutoff);
+        }
+        let profile = profile.unwrap_or_default();
+        let previous: Option<(f64, u64)> = connection
+            .prepare("SELECT score, updated_at FROM host_choices WHERE host = ? AND bundle_id = ? AND profile = ?")
+            .unwrap()
+            .query_map((&host, &bundle_id, &profile), |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap()
+            .map(|r| r.unwrap())
+            .next();
+        let score = previous
+            .map(|(score, updated_at)| decay(score, now.saturating_sub(updated_at)))
+            .unwrap_or(0.0)
+            + 1.0;
+        connection
+            .execute(
+                "INSERT OR REPLACE INTO host_choices (host, bundle_id, profile, score, updated_at) VALUES (?, ?, ?, ?, ?)",
+                (&host, &bundle_id, &profile, score, now),
+            )
+            .unwrap();
+        connection
+            .execute(
+                "INSERT INTO host_streaks (host, bundle_id, profile, count) VALUES (?, ?, ?, 1) ON CONFLICT(host) DO UPDATE SET count = CASE WHEN bundle_id = excluded.bundle_id AND profile = excluded.profile THEN count + 1 ELSE 1 END, bundle_id = excluded.bundle_id, profile = excluded.profile",
+                (&host, &bundle_id, &profile),
+            )
+            .unwrap();
+        connection
+            .query_row(
+                "SELECT count FROM host_streaks WHERE host = ?",
+                [&host],
+                |row| row.get(0),
+            )
+            .unwrap()
     }
-
-    pub fn get_browsers(&self) -> Vec<BrowserInfo> {
+    pub fn get_host_streak(&self, host: String) -> Option<HostStreak> {
         let connection = self.connection.lock().unwrap();
         let mut stmt = connection
-            .prepare("SELECT name, path, icon_data FROM browsers")
+            .prepare("SELECT bundle_id, profile, count FROM host_streaks WHERE host = ?")
             .unwrap();
-        stmt.query_map([], |row| {
-            Ok(BrowserInfo {
-                name: row.get(0)?,
-                path: row.get(1)?,
-                icon_data: row.get(2)?,
+        let streak = stmt
+            .query_map([host], |row| {
+                let profile: String = row.get(1)?;
+                Ok(HostStreak {
+                    bundle_id: row.get(0)?,
+                    profile: (!profile.is_empty()).then_some(profile),
+                    count: row.get(2)?,
+                })
             })
-        })
-        .unwrap()
-        .map(|r| r.unwrap())
-        .collect()
+            .unwrap()
+            .map(|r| r.unwrap())
+            .next();
+        streak
     }
-    pub fn insert_match(&self, match_item: MatchItem) {
+    pub fn reset_host_streak(&self, host: String) {
         let connection = self.connection.lock().unwrap();
         connection
-            .execute(
-                "INSERT INTO matches (browser_path, profile, match_type, match_value) VALUES (?, ?, ?, ?) ON CONFLICT(match_value) DO NOTHING",
-                (match_item.browser_path, match_item.profile, match_item.match_type, match_item.match_value),
-            )
+            .execute("DELETE FROM host_streaks WHERE host = ?", [host])
             .unwrap();
     }
-    pub fn find_equal_matches_by_url(&self, url: String) -> Option<MatchItem> {
+    pub fn get_host_choices(&self, host: String, now: u64) -> Vec<HostChoice> {
         let connection = self.connection.lock().unwrap();
         let mut stmt = connection
-            .prepare("SELECT * FROM matches WHERE match_type = ? AND match_value = ?")
+            .prepare(
+                "SELECT bundle_id, profile, score, updated_at FROM host_choices WHERE host = ?",
+            )
             .unwrap();
-        let result: Vec<MatchItem> = stmt
-            .query_map(["Equal", &url], |row| {
-                Ok(MatchItem {
-                    browser_path: row.get(0)?,
-                    profile: row.get(1)?,
-                    match_type: row.get(2)?,
-                    match_value: row.get(3)?,
+        let mut choices: Vec<HostChoice> = stmt
+            .query_map([&host], |row| {
+                let profile: String = row.get(1)?;
+                let updated_at: u64 = row.get(3)?;
+                Ok(HostChoice {
+                    bundle_id: row.get(0)?,
+                    profile: (!profile.is_empty()).then_some(profile),
+                    score: decay(row.get(2)?, now.saturating_sub(updated_at)),
                 })
             })
             .unwrap()
             .map(|r| r.unwrap())
+            .filter(|choice| choice.score >= MIN_CHOICE_SCORE)
             .collect();
-        if result.is_empty() {
-            None
-        } else {
-            Some(result[0].clone())
+        choices.sort_by(|a, b| b.score.total_cmp(&a.score));
+        choices
+    }
+    pub fn clear_host_choices(&self) {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute_batch("DELETE FROM host_choices; DELETE FROM host_streaks;")
+            .unwrap();
+    }
+    pub fn insert_history(&self, entry: HistoryEntry) {
+        let policy = self.privacy.lock().unwrap().clone();
+        if url_host(&entry.url).is_some_and(|host| policy.is_excluded(&host)) {
+            return;
         }
+        let entry = HistoryEntry {
+            url: self.history_url(&policy, &entry.url),
+            ..entry
+        };
+        let connection = self.connection.lock().unwrap();
+        if let Some(cutoff) = policy.cutoff(entry.opened_at) {
+            prune_before(&connection, cutoff);
+        }
+        connection
+            .execute(
+                "INSERT INTO history (url, opened_at, source, match_type, match_value, bundle_id, profile) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                (entry.url, entry.opened_at, entry.source, entry.match_type, entry.match_value, entry.bundle_id, entry.profile),
+            )
+            .unwrap();
     }
-    pub fn find_contain_matches_by_url(&self, url: String) -> Option<MatchItem> {
+    pub fn find_history_urls_containing(&self, pattern: &str, limit: u32) -> (u32, Vec<String>) {
         let connection = self.connection.lock().unwrap();
+        let count = connection
+            .query_row(
+                "SELECT count(DISTINCT url) FROM history WHERE instr(url, ?) > 0",
+                [pattern],
+                |row| row.get(0),
+            )
+            .unwrap();
         let mut stmt = connection
-            .prepare("SELECT * FROM matches WHERE match_type = ?")
+            .prepare("SELECT url FROM history WHERE instr(url, ?) > 0 GROUP BY url ORDER BY max(opened_at) DESC LIMIT ?")
             .unwrap();
-        let result: Vec<MatchItem> = stmt
-            .query_map(["Contain"], |row| {
-                Ok(MatchItem {
-                    browser_path: row.get(0)?,
-                    profile: row.get(1)?,
-                    match_type: row.get(2)?,
-                    match_value: row.get(3)?,
-                })
-            })
+        let urls = stmt
+            .query_map((pattern, limit), |row| row.get(0))
             .unwrap()
             .map(|r| r.unwrap())
             .collect();
-        let matched = result
-            .iter()
-            .filter(|item| url.contains(&item.match_value))
-            .next();
-        matched.cloned()
+        (count, urls)
     }
-    pub fn insert_browser_profile(&self, browser_profile: BrowserProfile) {
+    pub fn search_history(
+        &self,
+        query: &str,
+        bundle_id: Option<String>,
+        since: Option<u64>,
+    ) -> Vec<HistoryEntry> {
         let connection = self.connection.lock().unwrap();
-        connection
-            .execute(
-                "INSERT INTO browser_profiles (browser_path, profile, description) VALUES (?, ?, ?) ON CONFLICT(browser_path, profile) DO NOTHING",
-                (browser_profile.browser_path, browser_profile.profile, browser_profile.description),
-            )
-            .unwrap();
+        let query = fts_query(query);
+        let mut sql = format!("SELECT {} FROM history", HISTORY_COLUMNS);
+        if !query.is_empty() {
+            sql.push_str(
+                " JOIN history_fts ON history_fts.rowid = history.id AND history_fts MATCH :query",
+            );
+        }
+        sql.push_str(" WHERE (:bundle_id IS NULL OR bundle_id = :bundle_id) AND opened_at >= :since ORDER BY opened_at DESC, history.id DESC LIMIT :limit");
+        let mut stmt = connection.prepare(&sql).unwrap();
+        let since = since.unwrap_or(0);
+        let mut params: Vec<(&str, &dyn rusqlite::ToSql)> = vec![
+            (":bundle_id", &bundle_id),
+            (":since", &since),
+            (":limit", &HISTORY_LIMIT),
+        ];
+        if !query.is_empty() {
+            params.push((":query", &query));
+        }
+        let entries = stmt
+            .query_map(params.as_slice(), history_entry_from_row)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        entries
     }
-    pub fn get_browser_profiles(&self, browser_path: String) -> Vec<BrowserProfile> {
+    pub fn get_setting(&self, key: &str) -> Option<String> {
         let connection = self.connection.lock().unwrap();
         let mut stmt = connection
-            .prepare("SELECT * FROM browser_profiles WHERE browser_path = ?")
+            .prepare("SELECT value FROM settings WHERE key = ?")
             .unwrap();
-        stmt.query_map([browser_path], |row| {
-            Ok(BrowserProfile {
-                browser_path: row.get(0)?,
-                profile: row.get(1)?,
-                description: row.get(2)?,
-            })
-        })
-        .unwrap()
-        .map(|r| r.unwrap())
-        .collect()
+        let value = stmt
+            .query_map([key], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .next();
+        value
     }
-    pub fn delete_browser_profile(&self, browser_path: String, profile: String) {
+    pub fn set_setting(&self, key: &str, value: &str) {
         let connection = self.connection.lock().unwrap();
         connection
             .execute(
-                "DELETE FROM browser_profiles WHERE browser_path = ? AND profile = ?",
-                (browser_path, profile),
+                "INSERT INTO settings (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                (key, value),
             )
             .unwrap();
     }
-    pub fn delete_match_by_profile_and_browser_path(&self, browser_path: String, profile: String) {
-        let connection = self.connection.lock().unwrap();
+}
+
+fn prune_before(connection: &Connection, cutoff: u64) {
+    connection
+        .execute("DELETE FROM history WHERE opened_at < ?", [cutoff])
+        .unwrap();
+    connection
+        .execute("DELETE FROM host_choices WHERE updated_at < ?", [cutoff])
+        .unwrap();
+}
+
+fn url_hash_salt(connection: &Connection) -> String {
+    let salt: Option<String> = connection
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            [URL_HASH_SALT_KEY],
+            |row| row.get(0),
+        )
+        .ok();
+    salt.unwrap_or_else(|| {
+        let mut bytes = [0u8; 16];
+        getrandom::getrandom(&mut bytes).unwrap();
+        let salt = hex(&bytes);
         connection
             .execute(
-                "DELETE FROM matches WHERE browser_path = ? AND profile = ?",
-                (browser_path, profile),
+                "INSERT INTO settings (key, value) VALUES (?, ?)",
+                (URL_HASH_SALT_KEY, &salt),
             )
             .unwrap();
-    }
-    pub fn delete_match_by_match_value(&self, match_value: String) {
-        let connection = self.connection.lock().unwrap();
+        salt
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn fts_query(query: &str) -> String {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| format!("\"{}\"*", token))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn decay(score: f64, elapsed_secs: u64) -> f64 {
+    score * 0.5f64.powf(elapsed_secs as f64 / CHOICE_HALF_LIFE_SECS as f64)
+}
+
+fn has_table(connection: &Connection, table: &str) -> bool {
+    let mut stmt = connection
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .unwrap();
+    stmt.exists([table]).unwrap()
+}
+
+fn has_trigger(connection: &Connection, trigger: &str) -> bool {
+    let mut stmt = connection
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'trigger' AND name = ?")
+        .unwrap();
+    stmt.exists([trigger]).unwrap()
+}
+
+fn has_column(connection: &Connection, table: &str, column: &str) -> bool {
+    let mut stmt = connection
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .unwrap();
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .unwrap()
+        .any(|name| name.unwrap() == column);
+    exists
+}
+
+fn ensure_column(connection: &Connection, table: &str, column: &str, definition: &str) {
+    if !has_column(connection, table, column) {
         connection
-            .execute("DELETE FROM matches WHERE match_value = ?", (match_value,))
+            .execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                (),
+            )
             .unwrap();
     }
-    pub fn find_all_match_items(&self) -> Vec<MatchItem> {
-        let connection = self.connection.lock().unwrap();
-        let mut stmt = connection.prepare("SELECT * FROM matches").unwrap();
-        stmt.query_map([], |row| {
-            Ok(MatchItem {
-                browser_path: row.get(0)?,
-                profile: row.get(1)?,
-                match_type: row.get(2)?,
-                match_value: row.get(3)?,
-            })
-        })
-        .unwrap()
-        .map(|r| r.unwrap())
-        .collect()
-    }
+}
+
+fn browser_info_from_row(row: &Row) -> rusqlite::Result<BrowserInfo> {
+    Ok(BrowserInfo {
+        bundle_id: row.get(0)?,
+        name: row.get(1)?,
+        path: row.get(2)?,
+        icon_data: row.get(3)?,
+        launch_command: row.get(4)?,
+        hidden: row.get(5)?,
+    })
+}
+
+fn browser_profile_from_row(row: &Row) -> rusqlite::Result<BrowserProfile> {
+    Ok(BrowserProfile {
+        bundle_id: row.get(0)?,
+        browser_path: row.get(1)?,
+        profile: row.get(2)?,
+        description: row.get(3)?,
+        account: row.get(4)?,
+    })
+}
+
+fn match_item_from_row(row: &Row) -> rusqlite::Result<MatchItem> {
+    Ok(MatchItem {
+        bundle_id: row.get(0)?,
+        browser_path: row.get(1)?,
+        profile: row.get(2)?,
+        match_type: row.get(3)?,
+        match_value: row.get(4)?,
+        private: row.get(5)?,
+        auto_created: row.get(6)?,
+    })
+}
+
+fn history_entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        opened_at: row.get(2)?,
+        source: row.get(3)?,
+        match_type: row.get(4)?,
+        match_value: row.get(5)?,
+        bundle_id: row.get(6)?,
+        profile: row.get(7)?,
+    })
 }
 
 #[cfg(test)]
@@ -207,27 +1157,37 @@ mod tests {
     use std::fs;
     use std::path::Path;
 
-    fn cleanup_database() {
-        let db_path = get_db_path();
+    fn test_db_path(name: &str) -> String {
+        let db_path = std::env::temp_dir()
+            .join(format!("gomi-test-{}.db", name))
+            .to_string_lossy()
+            .to_string();
         if Path::new(&db_path).exists() {
             fs::remove_file(&db_path).unwrap();
         }
+        db_path
     }
 
     #[test]
     fn test_browser_crud() {
-        cleanup_database();
-        let mut storage = Storage::new();
+        let db_path = test_db_path("browser_crud");
+        let mut storage = Storage::open(&db_path);
         let test_browsers = vec![
             BrowserInfo {
+                bundle_id: "com.test.browser1".to_string(),
                 name: "Browser 1".to_string(),
                 path: "/test/path1".to_string(),
                 icon_data: vec![1, 2, 3],
+                launch_command: None,
+                hidden: false,
             },
             BrowserInfo {
+                bundle_id: "com.test.browser2".to_string(),
                 name: "Browser 2".to_string(),
                 path: "/test/path2".to_string(),
                 icon_data: vec![4, 5, 6],
+                launch_command: None,
+                hidden: false,
             },
         ];
         storage.batch_insert_browsers(test_browsers);
@@ -236,58 +1196,283 @@ mod tests {
         assert_eq!(browsers.len(), 2);
         assert_eq!(browsers[1].name, "Browser 2");
         assert_eq!(browsers[1].icon_data, vec![4, 5, 6]);
+
+        storage.insert_match(MatchItem {
+            bundle_id: "com.test.browser1".to_string(),
+            browser_path: "/test/path1".to_string(),
+            profile: None,
+            match_type: "Contain".to_string(),
+            match_value: "example.com".to_string(),
+            private: false,
+            auto_created: false,
+        });
+        storage.batch_insert_browsers(vec![BrowserInfo {
+            bundle_id: "com.test.browser1".to_string(),
+            name: "Browser 1".to_string(),
+            path: "/moved/path1".to_string(),
+            icon_data: vec![1, 2, 3],
+            launch_command: None,
+            hidden: false,
+        }]);
+        assert_eq!(storage.get_browsers().len(), 2);
+        let browser = storage
+            .get_browser("com.test.browser1".to_string())
+            .unwrap();
+        assert_eq!(browser.path, "/moved/path1");
+        let matched = storage
+            .find_contain_matches_by_url("https://example.com".to_string())
+            .unwrap();
+        assert_eq!(matched.browser_path, "/moved/path1");
+        assert!(storage
+            .get_browser("com.test.missing".to_string())
+            .is_none());
+    }
+
+    #[test]
+    fn test_reconcile_browsers() {
+        let db_path = test_db_path("reconcile_browsers");
+        let mut storage = Storage::open(&db_path);
+        let browser = |bundle_id: &str| BrowserInfo {
+            bundle_id: bundle_id.to_string(),
+            name: bundle_id.to_string(),
+            path: format!("/Applications/{}.app", bundle_id),
+            icon_data: vec![1],
+            launch_command: None,
+            hidden: false,
+        };
+        let removed =
+            storage.reconcile_browsers(vec![browser("com.test.one"), browser("com.test.two")]);
+        assert!(removed.is_empty());
+        assert_eq!(storage.get_browsers().len(), 2);
+
+        let removed = storage.reconcile_browsers(vec![browser("com.test.two")]);
+        assert_eq!(removed, vec![browser("com.test.one")]);
+        assert_eq!(storage.get_browsers(), vec![browser("com.test.two")]);
+        assert_eq!(
+            storage.get_missing_browsers(),
+            vec![browser("com.test.one")]
+        );
+        assert!(storage.get_browser("com.test.one".to_string()).is_none());
+
+        let removed = storage.reconcile_browsers(vec![browser("com.test.two")]);
+        assert!(removed.is_empty());
+
+        let removed =
+            storage.reconcile_browsers(vec![browser("com.test.one"), browser("com.test.two")]);
+        assert!(removed.is_empty());
+        assert_eq!(storage.get_browsers().len(), 2);
+        assert!(storage.get_missing_browsers().is_empty());
+    }
+
+    #[test]
+    fn test_browser_presentation() {
+        let db_path = test_db_path("browser_presentation");
+        let mut storage = Storage::open(&db_path);
+        let browser = |bundle_id: &str, name: &str| BrowserInfo {
+            bundle_id: bundle_id.to_string(),
+            name: name.to_string(),
+            path: format!("/Applications/{}.app", name),
+            icon_data: vec![1],
+            launch_command: None,
+            hidden: false,
+        };
+        storage.batch_insert_browsers(vec![
+            browser("com.test.zen", "zen"),
+            browser("com.test.arc", "Arc"),
+            browser("com.test.brave", "Brave"),
+        ]);
+        let names = |storage: &Storage| -> Vec<String> {
+            storage
+                .get_browsers()
+                .into_iter()
+                .map(|browser| browser.name)
+                .collect()
+        };
+        assert_eq!(names(&storage), vec!["Arc", "Brave", "zen"]);
+
+        storage.set_browser_order(vec![
+            "com.test.brave".to_string(),
+            "com.test.arc".to_string(),
+        ]);
+        storage.rename_browser("com.test.arc".to_string(), Some("Work".to_string()));
+        storage.set_browser_hidden("com.test.brave".to_string(), true);
+        storage.batch_insert_browsers(vec![browser("com.test.arc", "Arc")]);
+        assert_eq!(names(&storage), vec!["Brave", "Work", "zen"]);
+        assert!(storage.get_browsers()[0].hidden);
+        assert_eq!(
+            storage
+                .get_browser("com.test.arc".to_string())
+                .unwrap()
+                .name,
+            "Work"
+        );
+
+        storage.rename_browser("com.test.arc".to_string(), None);
+        assert_eq!(names(&storage), vec!["Brave", "Arc", "zen"]);
+    }
+
+    #[test]
+    fn test_manual_browsers() {
+        let db_path = test_db_path("manual_browsers");
+        let mut storage = Storage::open(&db_path);
+        let command_browser = BrowserInfo {
+            bundle_id: "custom.vm".to_string(),
+            name: "VM".to_string(),
+            path: "/usr/local/bin/vm-open".to_string(),
+            icon_data: vec![],
+            launch_command: Some("/usr/local/bin/vm-open --url {url}".to_string()),
+            hidden: false,
+        };
+        assert!(!storage.is_bundle_id_used("custom.vm"));
+        storage.insert_manual_browser(command_browser.clone());
+        assert!(storage.is_bundle_id_used("custom.vm"));
+        let discovered = BrowserInfo {
+            bundle_id: "com.test.discovered".to_string(),
+            name: "Discovered".to_string(),
+            path: "/test/discovered".to_string(),
+            icon_data: vec![1],
+            launch_command: None,
+            hidden: false,
+        };
+        let removed = storage.reconcile_browsers(vec![discovered.clone()]);
+        assert!(removed.is_empty());
+        assert_eq!(
+            storage.get_browser("custom.vm".to_string()),
+            Some(command_browser.clone())
+        );
+        assert_eq!(storage.get_manual_browsers(), vec![command_browser.clone()]);
+        assert_eq!(storage.get_browsers().len(), 2);
+
+        storage.delete_manual_browser("com.test.discovered".to_string());
+        storage.delete_manual_browser("custom.vm".to_string());
+        assert_eq!(storage.get_browsers(), vec![discovered]);
+        assert!(storage.get_manual_browsers().is_empty());
+        assert!(!storage.is_bundle_id_used("custom.vm"));
+        storage.insert_match(MatchItem {
+            bundle_id: "custom.vm".to_string(),
+            browser_path: "/usr/local/bin/vm-open".to_string(),
+            profile: None,
+            match_type: "Host".to_string(),
+            match_value: "vm.local".to_string(),
+            private: false,
+            auto_created: false,
+        });
+        assert!(storage.is_bundle_id_used("custom.vm"));
     }
 
     #[test]
     fn test_profile_operations() {
-        cleanup_database();
-        let storage = Storage::new();
+        let db_path = test_db_path("profile_operations");
+        let storage = Storage::open(&db_path);
 
         let profiles = vec![
             BrowserProfile {
+                bundle_id: "com.test.browser1".to_string(),
                 browser_path: "/test/path1".to_string(),
                 profile: "Default".to_string(),
                 description: Some("Default Profile".to_string()),
+                account: Some("me@example.com".to_string()),
             },
             BrowserProfile {
+                bundle_id: "com.test.browser1".to_string(),
                 browser_path: "/test/path1".to_string(),
                 profile: "Work".to_string(),
                 description: None,
+                account: None,
             },
         ];
 
         for profile in profiles {
             storage.insert_browser_profile(profile);
         }
-        let saved_profiles = storage.get_browser_profiles("Test Browser".to_string());
+        let saved_profiles = storage.get_browser_profiles("com.test.browser1".to_string());
         assert_eq!(saved_profiles.len(), 2);
         assert!(saved_profiles
             .iter()
             .any(|p| p.profile == "Work" && p.description.is_none()));
 
-        storage.delete_browser_profile("Test Browser".to_string(), "Work".to_string());
-        let remaining_profiles = storage.get_browser_profiles("Test Browser".to_string());
+        storage.insert_browser_profile(BrowserProfile {
+            bundle_id: "com.test.browser1".to_string(),
+            browser_path: "/test/path1".to_string(),
+            profile: "Work".to_string(),
+            description: Some("Work".to_string()),
+            account: Some("me@corp.example".to_string()),
+        });
+        storage.insert_browser_profile(BrowserProfile {
+            bundle_id: "com.test.browser1".to_string(),
+            browser_path: "/test/path1".to_string(),
+            profile: "Work".to_string(),
+            description: None,
+            account: None,
+        });
+        let saved_profiles = storage.get_browser_profiles("com.test.browser1".to_string());
+        assert_eq!(saved_profiles.len(), 2);
+        assert_eq!(saved_profiles[1].description, Some("Work".to_string()));
+        assert_eq!(
+            saved_profiles[1].account,
+            Some("me@corp.example".to_string())
+        );
+
+        storage.delete_browser_profile("com.test.browser1".to_string(), "Work".to_string());
+        let remaining_profiles = storage.get_browser_profiles("com.test.browser1".to_string());
         assert_eq!(remaining_profiles.len(), 1);
         assert_eq!(remaining_profiles[0].profile, "Default");
     }
 
+    #[test]
+    fn test_dismissed_profiles() {
+        let db_path = test_db_path("dismissed_profiles");
+        let storage = Storage::open(&db_path);
+        let profile = |name: &str| BrowserProfile {
+            bundle_id: "com.google.Chrome".to_string(),
+            browser_path: "/Applications/Google Chrome.app".to_string(),
+            profile: name.to_string(),
+            description: Some(name.to_string()),
+            account: None,
+        };
+        let profile_names = || {
+            storage
+                .get_browser_profiles("com.google.Chrome".to_string())
+                .into_iter()
+                .map(|profile| profile.profile)
+                .collect::<Vec<_>>()
+        };
+        storage.insert_discovered_profiles(vec![profile("Default"), profile("Profile 1")]);
+        assert_eq!(profile_names(), vec!["Default", "Profile 1"]);
+
+        storage.delete_browser_profile("com.google.Chrome".to_string(), "Profile 1".to_string());
+        storage.insert_discovered_profiles(vec![profile("Default"), profile("Profile 1")]);
+        assert_eq!(profile_names(), vec!["Default"]);
+
+        storage.insert_browser_profile(profile("Profile 1"));
+        storage.delete_browser_profile("com.google.Chrome".to_string(), "Default".to_string());
+        storage.insert_discovered_profiles(vec![profile("Default"), profile("Profile 1")]);
+        assert_eq!(profile_names(), vec!["Profile 1"]);
+    }
+
     #[test]
     fn test_match_rules() {
-        cleanup_database();
-        let storage = Storage::new();
+        let db_path = test_db_path("match_rules");
+        let storage = Storage::open(&db_path);
 
         let matches = vec![
             MatchItem {
+                bundle_id: "com.test.browser1".to_string(),
                 browser_path: "/test/path1".to_string(),
                 profile: Some("Default".to_string()),
                 match_type: "Equal".to_string(),
                 match_value: "https://example.com".to_string(),
+                private: false,
+                auto_created: false,
             },
             MatchItem {
+                bundle_id: "com.test.browser2".to_string(),
                 browser_path: "/test/path2".to_string(),
                 profile: None,
                 match_type: "Contain".to_string(),
                 match_value: "github.com".to_string(),
+                private: true,
+                auto_created: false,
             },
         ];
 
@@ -298,6 +1483,7 @@ mod tests {
         let exact_match = storage.find_equal_matches_by_url("https://example.com".to_string());
         assert!(exact_match.is_some());
         let matched = exact_match.unwrap();
+        assert_eq!(matched.bundle_id, "com.test.browser1");
         assert_eq!(matched.browser_path, "/test/path1");
         assert_eq!(matched.profile, Some("Default".to_string()));
         let contain_match = storage.find_contain_matches_by_url("test.github.com".to_string());
@@ -305,6 +1491,11 @@ mod tests {
         let matched = contain_match.unwrap();
         assert_eq!(matched.browser_path, "/test/path2");
         assert_eq!(matched.profile, None);
+        assert!(matched.private);
+
+        storage.set_match_private("github.com".to_string(), false);
+        let contain_match = storage.find_contain_matches_by_url("test.github.com".to_string());
+        assert!(!contain_match.unwrap().private);
 
         assert!(storage
             .find_equal_matches_by_url("https://other.com".to_string())
@@ -314,10 +1505,85 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn test_orphaned_rules() {
+        let db_path = test_db_path("orphaned_rules");
+        let mut storage = Storage::open(&db_path);
+        storage.batch_insert_browsers(vec![BrowserInfo {
+            bundle_id: "com.test.installed".to_string(),
+            name: "Installed".to_string(),
+            path: "/test/installed".to_string(),
+            icon_data: vec![1],
+            launch_command: None,
+            hidden: false,
+        }]);
+        storage.insert_browser_profile(BrowserProfile {
+            bundle_id: "com.test.installed".to_string(),
+            browser_path: "/test/installed".to_string(),
+            profile: "Default".to_string(),
+            description: None,
+            account: None,
+        });
+        storage.insert_browser_profile(BrowserProfile {
+            bundle_id: "com.test.removed".to_string(),
+            browser_path: "/test/removed".to_string(),
+            profile: "Work".to_string(),
+            description: None,
+            account: None,
+        });
+        let rule = |bundle_id: &str, profile: Option<&str>, match_value: &str| MatchItem {
+            bundle_id: bundle_id.to_string(),
+            browser_path: format!("/test/{}", bundle_id),
+            profile: profile.map(|profile| profile.to_string()),
+            match_type: "Contain".to_string(),
+            match_value: match_value.to_string(),
+            private: false,
+            auto_created: false,
+        };
+        storage.insert_match(rule("com.test.installed", None, "healthy.com"));
+        storage.insert_match(rule("com.test.installed", Some("Default"), "profile.com"));
+        storage.insert_match(rule("com.test.installed", Some("Gone"), "gone-profile.com"));
+        storage.insert_match(rule("com.test.removed", Some("Work"), "removed.com"));
+        storage.insert_match(rule("gomi.action.clipboard", None, "copy.com"));
+
+        let orphaned: Vec<String> = storage
+            .find_orphaned_match_items()
+            .into_iter()
+            .map(|item| item.match_value)
+            .collect();
+        assert_eq!(orphaned, vec!["gone-profile.com", "removed.com"]);
+        let orphaned_profiles = storage.find_orphaned_browser_profiles();
+        assert_eq!(orphaned_profiles.len(), 1);
+        assert_eq!(orphaned_profiles[0].bundle_id, "com.test.removed");
+
+        storage.reassign_matches(
+            orphaned,
+            "com.test.installed".to_string(),
+            "/test/installed".to_string(),
+            Some("Default".to_string()),
+        );
+        assert!(storage.find_orphaned_match_items().is_empty());
+        let matched = storage
+            .find_contain_matches_by_url("https://removed.com".to_string())
+            .unwrap();
+        assert_eq!(matched.bundle_id, "com.test.installed");
+        assert_eq!(matched.browser_path, "/test/installed");
+        assert_eq!(matched.profile, Some("Default".to_string()));
+
+        storage.delete_orphaned_browser_profiles();
+        assert!(storage.find_orphaned_browser_profiles().is_empty());
+        assert_eq!(
+            storage
+                .get_browser_profiles("com.test.installed".to_string())
+                .len(),
+            1
+        );
+    }
+
     #[test]
     fn test_empty_database() {
-        cleanup_database();
-        let storage = Storage::new();
+        let db_path = test_db_path("empty_database");
+        let storage = Storage::open(&db_path);
         assert!(storage.get_browsers().is_empty());
         assert!(storage
             .get_browser_profiles("NonExistent".to_string())
@@ -329,4 +1595,675 @@ mod tests {
             .find_contain_matches_by_url("any".to_string())
             .is_none());
     }
+
+    #[test]
+    fn test_settings() {
+        let db_path = test_db_path("settings");
+        let storage = Storage::open(&db_path);
+        assert!(storage.get_setting("private_modifier").is_none());
+        storage.set_setting("private_modifier", "alt");
+        storage.set_setting("private_modifier", "control");
+        assert_eq!(
+            storage.get_setting("private_modifier"),
+            Some("control".to_string())
+        );
+    }
+
+    #[test]
+    fn test_host_choices() {
+        let db_path = test_db_path("host_choices");
+        let storage = Storage::open(&db_path);
+        let host = "github.com".to_string();
+        let record = |bundle_id: &str, profile: Option<&str>, now: u64| {
+            storage.record_host_choice(
+                host.clone(),
+                bundle_id.to_string(),
+                profile.map(str::to_string),
+                now,
+            )
+        };
+        record("com.google.Chrome", None, 0);
+        record("com.google.Chrome", None, 0);
+        record("com.google.Chrome", Some("Profile 1"), 0);
+        storage.record_host_choice(
+            "example.com".to_string(),
+            "org.mozilla.firefox".to_string(),
+            None,
+            0,
+        );
+
+        let choices = storage.get_host_choices(host.clone(), 0);
+        assert_eq!(
+            choices,
+            vec![
+                HostChoice {
+                    bundle_id: "com.google.Chrome".to_string(),
+                    profile: None,
+                    score: 2.0,
+                },
+                HostChoice {
+                    bundle_id: "com.google.Chrome".to_string(),
+                    profile: Some("Profile 1".to_string()),
+                    score: 1.0,
+                },
+            ]
+        );
+
+        // Two half-lives later the old habit has faded below a single fresh choice.
+        let later = 2 * CHOICE_HALF_LIFE_SECS;
+        record("org.mozilla.firefox", None, later);
+        let choices = storage.get_host_choices(host.clone(), later);
+        assert_eq!(choices[0].bundle_id, "org.mozilla.firefox");
+        assert_eq!(choices[1].score, 0.5);
+        record("com.google.Chrome", None, later);
+        assert_eq!(storage.get_host_choices(host.clone(), later)[0].score, 1.5);
+        assert!(storage
+            .get_host_choices(host.clone(), 20 * CHOICE_HALF_LIFE_SECS)
+            .is_empty());
+
+        storage.clear_host_choices();
+        assert!(storage.get_host_choices(host.clone(), 0).is_empty());
+        assert_eq!(storage.get_host_streak(host), None);
+        assert!(storage
+            .get_host_choices("example.com".to_string(), 0)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_host_streaks() {
+        let db_path = test_db_path("host_streaks");
+        let storage = Storage::open(&db_path);
+        let record = |bundle_id: &str, profile: Option<&str>| {
+            storage.record_host_choice(
+                "github.com".to_string(),
+                bundle_id.to_string(),
+                profile.map(str::to_string),
+                0,
+            )
+        };
+        assert_eq!(record("com.google.Chrome", None), 1);
+        assert_eq!(record("com.google.Chrome", None), 2);
+        assert_eq!(record("com.google.Chrome", Some("Work")), 1);
+        assert_eq!(record("com.google.Chrome", Some("Work")), 2);
+        assert_eq!(
+            storage.record_host_choice(
+                "example.com".to_string(),
+                "org.mozilla.firefox".to_string(),
+                None,
+                0
+            ),
+            1
+        );
+        assert_eq!(
+            storage.get_host_streak("github.com".to_string()),
+            Some(HostStreak {
+                bundle_id: "com.google.Chrome".to_string(),
+                profile: Some("Work".to_string()),
+                count: 2,
+            })
+        );
+        storage.reset_host_streak("github.com".to_string());
+        assert_eq!(storage.get_host_streak("github.com".to_string()), None);
+        assert_eq!(record("com.google.Chrome", Some("Work")), 1);
+        assert!(storage.get_host_streak("example.com".to_string()).is_some());
+    }
+
+    #[test]
+    fn test_host_rules() {
+        let db_path = test_db_path("host_rules");
+        let storage = Storage::open(&db_path);
+        let rule = |bundle_id: &str, match_type: &str, match_value: &str| MatchItem {
+            bundle_id: bundle_id.to_string(),
+            browser_path: format!("/test/{}", bundle_id),
+            profile: None,
+            match_type: match_type.to_string(),
+            match_value: match_value.to_string(),
+            private: false,
+            auto_created: true,
+        };
+        storage.insert_match(rule("com.test.domain", "Domain", "example.com"));
+        storage.insert_match(rule("com.test.host", "Host", "docs.example.com"));
+
+        let find = |host: &str| {
+            storage
+                .find_host_matches(host.to_string())
+                .map(|item| item.bundle_id)
+        };
+        assert_eq!(find("docs.example.com").as_deref(), Some("com.test.host"));
+        assert_eq!(find("example.com").as_deref(), Some("com.test.domain"));
+        assert_eq!(find("api.example.com").as_deref(), Some("com.test.domain"));
+        assert_eq!(find("notexample.com"), None);
+        assert!(storage
+            .find_contain_matches_by_url("https://example.com/".to_string())
+            .is_none());
+        assert!(storage
+            .find_all_match_items()
+            .iter()
+            .all(|item| item.auto_created));
+    }
+
+    #[test]
+    fn test_rules_matching() {
+        let db_path = test_db_path("rules_matching");
+        let storage = Storage::open(&db_path);
+        let rule = |match_type: &str, match_value: &str| MatchItem {
+            bundle_id: "com.google.Chrome".to_string(),
+            browser_path: "/Applications/Google Chrome.app".to_string(),
+            profile: None,
+            match_type: match_type.to_string(),
+            match_value: match_value.to_string(),
+            private: false,
+            auto_created: false,
+        };
+        let url = "https://docs.github.com/en/rest";
+        storage.insert_match(rule("Domain", "github.com"));
+        storage.insert_match(rule("Contain", "/rest"));
+        storage.insert_match(rule("Host", "docs.github.com"));
+        storage.insert_match(rule("Equal", url));
+        storage.insert_match(rule("Prefix", "https://docs.github.com/en/"));
+        storage.insert_match(rule("Contain", "gitlab"));
+        storage.insert_match(rule("Host", "gist.github.com"));
+
+        assert_eq!(
+            storage
+                .find_rules_matching(url)
+                .into_iter()
+                .map(|item| item.match_type)
+                .collect::<Vec<_>>(),
+            vec!["Equal", "Prefix", "Contain", "Host", "Domain"]
+        );
+        assert_eq!(
+            storage
+                .find_rules_matching("https://github.com/")
+                .into_iter()
+                .map(|item| item.match_value)
+                .collect::<Vec<_>>(),
+            vec!["github.com"]
+        );
+        assert!(storage
+            .find_rules_matching("https://example.com/")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_history_urls_containing() {
+        let db_path = test_db_path("history_urls_containing");
+        let storage = Storage::open(&db_path);
+        for (url, opened_at) in [
+            ("https://github.com/a", 1),
+            ("https://github.com/b", 2),
+            ("https://github.com/a", 3),
+            ("https://gitlab.com/a", 4),
+        ] {
+            storage.insert_history(HistoryEntry {
+                id: 0,
+                url: url.to_string(),
+                opened_at,
+                source: None,
+                match_type: None,
+                match_value: None,
+                bundle_id: "com.google.Chrome".to_string(),
+                profile: None,
+            });
+        }
+        assert_eq!(
+            storage.find_history_urls_containing("github.com/", 5),
+            (
+                2,
+                vec![
+                    "https://github.com/a".to_string(),
+                    "https://github.com/b".to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            storage.find_history_urls_containing("/a", 1),
+            (2, vec!["https://gitlab.com/a".to_string()])
+        );
+        assert_eq!(
+            storage.find_history_urls_containing("GitHub", 5),
+            (0, vec![])
+        );
+    }
+
+    #[test]
+    fn test_prefix_rules() {
+        let db_path = test_db_path("prefix_rules");
+        let storage = Storage::open(&db_path);
+        let rule = |bundle_id: &str, match_value: &str| MatchItem {
+            bundle_id: bundle_id.to_string(),
+            browser_path: format!("/test/{}", bundle_id),
+            profile: None,
+            match_type: "Prefix".to_string(),
+            match_value: match_value.to_string(),
+            private: false,
+            auto_created: false,
+        };
+        storage.insert_match(rule("com.test.repo", "https://github.com/lighthx/"));
+        storage.insert_match(rule(
+            "com.test.issues",
+            "https://github.com/lighthx/gomi/issues/",
+        ));
+
+        let find = |url: &str| {
+            storage
+                .find_prefix_matches_by_url(url.to_string())
+                .map(|item| item.bundle_id)
+        };
+        assert_eq!(
+            find("https://github.com/lighthx/gomi/issues/12").as_deref(),
+            Some("com.test.issues")
+        );
+        assert_eq!(
+            find("https://github.com/lighthx/gomi/pulls").as_deref(),
+            Some("com.test.repo")
+        );
+        assert_eq!(find("https://github.com/rust-lang/rust"), None);
+        assert_eq!(find("http://github.com/lighthx/gomi"), None);
+    }
+
+    #[test]
+    fn test_history() {
+        let db_path = test_db_path("history");
+        let storage = Storage::open(&db_path);
+        let entry =
+            |url: &str, opened_at: u64, source: Option<&str>, bundle_id: &str| HistoryEntry {
+                id: 0,
+                url: url.to_string(),
+                opened_at,
+                source: source.map(str::to_string),
+                match_type: None,
+                match_value: None,
+                bundle_id: bundle_id.to_string(),
+                profile: None,
+            };
+        storage.insert_history(entry(
+            "https://github.com/rust-lang/rust",
+            100,
+            Some("Slack"),
+            "com.google.Chrome",
+        ));
+        storage.insert_history(HistoryEntry {
+            match_type: Some("Host".to_string()),
+            match_value: Some("docs.rs".to_string()),
+            profile: Some("Work".to_string()),
+            ..entry(
+                "https://docs.rs/rusqlite",
+                200,
+                Some("Mail"),
+                "org.mozilla.firefox",
+            )
+        });
+        storage.insert_history(entry(
+            "https://github.com/lighthx/Gomi/issues?q=\"bug\"",
+            300,
+            None,
+            "org.mozilla.firefox",
+        ));
+
+        let urls = |entries: Vec<HistoryEntry>| -> Vec<String> {
+            entries.into_iter().map(|entry| entry.url).collect()
+        };
+        let all = storage.search_history("", None, None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].opened_at, 300);
+        assert_eq!(all[1].profile.as_deref(), Some("Work"));
+        assert_eq!(all[1].match_value.as_deref(), Some("docs.rs"));
+        assert_eq!(
+            urls(storage.search_history("github", None, None)),
+            vec![
+                "https://github.com/lighthx/Gomi/issues?q=\"bug\"",
+                "https://github.com/rust-lang/rust",
+            ]
+        );
+        assert_eq!(
+            urls(storage.search_history("rust-la", None, None)),
+            vec!["https://github.com/rust-lang/rust"]
+        );
+        assert_eq!(storage.search_history("slack", None, None).len(), 1);
+        assert_eq!(storage.search_history("\"bug\" (", None, None).len(), 1);
+        assert_eq!(
+            urls(storage.search_history("github", Some("org.mozilla.firefox".to_string()), None)),
+            vec!["https://github.com/lighthx/Gomi/issues?q=\"bug\""]
+        );
+        assert_eq!(storage.search_history("", None, Some(200)).len(), 2);
+        assert!(storage.search_history("gitlab", None, None).is_empty());
+    }
+
+    fn history_entry(url: &str, opened_at: u64) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            url: url.to_string(),
+            opened_at,
+            source: None,
+            match_type: None,
+            match_value: None,
+            bundle_id: "com.google.Chrome".to_string(),
+            profile: None,
+        }
+    }
+
+    fn equal_rule(url: &str) -> MatchItem {
+        MatchItem {
+            bundle_id: "com.google.Chrome".to_string(),
+            browser_path: "/Applications/Google Chrome.app".to_string(),
+            profile: None,
+            match_type: "Equal".to_string(),
+            match_value: url.to_string(),
+            private: false,
+            auto_created: false,
+        }
+    }
+
+    fn history_urls(storage: &Storage) -> Vec<String> {
+        storage
+            .search_history("", None, None)
+            .into_iter()
+            .map(|entry| entry.url)
+            .collect()
+    }
+
+    #[test]
+    fn test_history_retention() {
+        let db_path = test_db_path("history_retention");
+        let storage = Storage::open(&db_path);
+        let day = 24 * 60 * 60;
+        let now = 100 * day;
+        storage.insert_history(history_entry("https://github.com/old", now - 10 * day));
+        storage.insert_history(history_entry("https://github.com/recent", now - 2 * day));
+
+        storage.set_privacy_policy(
+            PrivacyPolicy {
+                retention_days: 7,
+                ..PrivacyPolicy::default()
+            },
+            now,
+        );
+        assert_eq!(history_urls(&storage), vec!["https://github.com/recent"]);
+
+        storage.insert_history(history_entry("https://github.com/later", now + 8 * day));
+        assert_eq!(history_urls(&storage), vec!["https://github.com/later"]);
+        assert_eq!(storage.search_history("later", None, None).len(), 1);
+        assert!(storage.search_history("recent", None, None).is_empty());
+    }
+
+    #[test]
+    fn test_excluded_domains() {
+        let db_path = test_db_path("excluded_domains");
+        let storage = Storage::open(&db_path);
+        let now = 100 * 24 * 60 * 60;
+        storage.insert_history(history_entry("https://mybank.com/login?token=1", now));
+        storage.insert_history(history_entry("https://github.com/pulls", now));
+        storage.record_host_choice(
+            "online.mybank.com".to_string(),
+            "com.google.Chrome".to_string(),
+            None,
+            now,
+        );
+
+        storage.set_privacy_policy(
+            PrivacyPolicy {
+                excluded_domains: vec!["mybank.com".to_string()],
+                ..PrivacyPolicy::default()
+            },
+            now,
+        );
+        assert_eq!(history_urls(&storage), vec!["https://github.com/pulls"]);
+        assert!(storage.search_history("mybank", None, None).is_empty());
+        assert!(storage
+            .get_host_choices("online.mybank.com".to_string(), now)
+            .is_empty());
+        assert_eq!(
+            storage.get_host_streak("online.mybank.com".to_string()),
+            None
+        );
+
+        storage.insert_history(history_entry("https://www.mybank.com/account", now));
+        storage.insert_history(history_entry("mailto:someone@example.com", now));
+        assert_eq!(
+            history_urls(&storage),
+            vec!["mailto:someone@example.com", "https://github.com/pulls"]
+        );
+        assert_eq!(
+            storage.record_host_choice(
+                "mybank.com".to_string(),
+                "com.google.Chrome".to_string(),
+                None,
+                now
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn test_host_only_history() {
+        let db_path = test_db_path("host_only_history");
+        let storage = Storage::open(&db_path);
+        storage.insert_history(history_entry("https://github.com/pulls?token=1", 0));
+        storage.insert_match(equal_rule("https://github.com/pulls?token=1"));
+
+        storage.set_privacy_policy(
+            PrivacyPolicy {
+                url_storage: UrlStorage::HostOnly,
+                ..PrivacyPolicy::default()
+            },
+            0,
+        );
+        assert_eq!(history_urls(&storage), vec!["https://github.com"]);
+        assert!(storage.search_history("pulls", None, None).is_empty());
+        assert_eq!(storage.search_history("github", None, None).len(), 1);
+        assert_eq!(
+            storage.find_all_match_items()[0].match_value,
+            "https://github.com/pulls?token=1"
+        );
+
+        storage.insert_history(history_entry("https://example.com:8443/a?b=c", 1));
+        storage.insert_history(history_entry("mailto:someone@example.com", 2));
+        assert_eq!(
+            history_urls(&storage),
+            vec!["mailto:", "https://example.com:8443", "https://github.com"]
+        );
+    }
+
+    #[test]
+    fn test_hash_exact_rules() {
+        let db_path = test_db_path("hash_exact_rules");
+        let storage = Storage::open(&db_path);
+        storage.insert_history(history_entry("https://github.com/pulls?token=1", 0));
+        storage.insert_match(equal_rule("https://github.com/pulls?token=1"));
+
+        storage.set_privacy_policy(
+            PrivacyPolicy {
+                hash_exact_rules: true,
+                ..PrivacyPolicy::default()
+            },
+            0,
+        );
+        assert_eq!(
+            history_urls(&storage),
+            vec!["https://github.com/pulls?token=1"]
+        );
+        let hashed = storage.find_all_match_items();
+        assert_eq!(hashed.len(), 1);
+        assert!(hashed[0].is_hashed());
+        assert!(!hashed[0].match_value.contains("token"));
+        assert!(storage
+            .find_equal_matches_by_url("https://github.com/pulls?token=1".to_string())
+            .is_some());
+        assert!(storage
+            .find_equal_matches_by_url("https://github.com/pulls?token=2".to_string())
+            .is_none());
+
+        storage.insert_match(equal_rule("https://docs.rs/rusqlite?token=3"));
+        assert!(storage
+            .find_all_match_items()
+            .iter()
+            .all(|item| item.match_value.starts_with(URL_HASH_PREFIX)));
+        assert!(storage
+            .find_equal_matches_by_url("https://docs.rs/rusqlite?token=3".to_string())
+            .is_some());
+        assert_eq!(storage.find_host_matches("docs.rs".to_string()), None);
+    }
+
+    #[test]
+    fn test_purge_history() {
+        let db_path = test_db_path("purge_history");
+        let storage = Storage::open(&db_path);
+        storage.insert_history(history_entry("https://github.com/pulls", 0));
+        storage.insert_match(equal_rule("https://github.com/pulls"));
+        storage.record_host_choice(
+            "github.com".to_string(),
+            "com.google.Chrome".to_string(),
+            None,
+            0,
+        );
+
+        storage.purge_history();
+        assert!(storage.search_history("", None, None).is_empty());
+        assert!(storage
+            .get_host_choices("github.com".to_string(), 0)
+            .is_empty());
+        assert_eq!(storage.get_host_streak("github.com".to_string()), None);
+        assert_eq!(storage.find_all_match_items().len(), 1);
+    }
+
+    #[test]
+    fn test_url_storage_parse() {
+        assert_eq!(UrlStorage::parse("full_url"), Some(UrlStorage::FullUrl));
+        assert_eq!(UrlStorage::parse("host_only"), Some(UrlStorage::HostOnly));
+        assert_eq!(UrlStorage::parse("salted_hash"), None);
+        assert_eq!(UrlStorage::parse("everything"), None);
+    }
+
+    #[test]
+    fn test_privacy_policy_keeps_rules() {
+        let db_path = test_db_path("privacy_policy_keeps_rules");
+        let storage = Storage::open(&db_path);
+        let rule = |bundle_id: &str, match_type: &str, match_value: &str| MatchItem {
+            bundle_id: bundle_id.to_string(),
+            browser_path: format!("/Applications/{}.app", bundle_id),
+            profile: None,
+            match_type: match_type.to_string(),
+            match_value: match_value.to_string(),
+            private: false,
+            auto_created: false,
+        };
+        storage.insert_match(rule("com.work", "Contain", "github.com"));
+        storage.insert_match(rule("com.personal", "Equal", "https://github.com/"));
+        storage.insert_match(rule("com.personal", "Equal", "https://gitlab.com/a"));
+        storage.insert_match(rule("com.other", "Host", "gitlab.com"));
+
+        storage.set_privacy_policy(
+            PrivacyPolicy {
+                url_storage: UrlStorage::HostOnly,
+                hash_exact_rules: true,
+                ..PrivacyPolicy::default()
+            },
+            0,
+        );
+        let rules = storage.find_all_match_items();
+        assert_eq!(rules.len(), 4);
+        assert!(rules
+            .iter()
+            .any(|item| item.match_type == "Contain" && item.match_value == "github.com"));
+        assert!(rules
+            .iter()
+            .any(|item| item.match_type == "Host" && item.match_value == "gitlab.com"));
+        assert_eq!(
+            storage
+                .find_equal_matches_by_url("https://github.com/".to_string())
+                .map(|item| item.bundle_id),
+            Some("com.personal".to_string())
+        );
+        assert_eq!(
+            storage.find_equal_matches_by_url("https://github.com/other".to_string()),
+            None
+        );
+        assert_eq!(
+            storage
+                .find_host_matches("gitlab.com".to_string())
+                .map(|item| item.bundle_id),
+            Some("com.other".to_string())
+        );
+    }
+
+    #[test]
+    fn test_hashing_drops_plaintext_duplicates() {
+        let db_path = test_db_path("hashing_drops_plaintext_duplicates");
+        let storage = Storage::open(&db_path);
+        let rule = |bundle_id: &str| MatchItem {
+            bundle_id: bundle_id.to_string(),
+            browser_path: format!("/Applications/{}.app", bundle_id),
+            profile: None,
+            match_type: "Equal".to_string(),
+            match_value: "https://github.com/pulls?token=1".to_string(),
+            private: false,
+            auto_created: false,
+        };
+        let hashing = |hash_exact_rules: bool| PrivacyPolicy {
+            hash_exact_rules,
+            ..PrivacyPolicy::default()
+        };
+        storage.set_privacy_policy(hashing(true), 0);
+        storage.insert_match(rule("com.hashed"));
+        storage.set_privacy_policy(hashing(false), 0);
+        storage.insert_match(rule("com.plain"));
+        assert_eq!(storage.find_all_match_items().len(), 2);
+
+        storage.set_privacy_policy(hashing(true), 0);
+        let rules = storage.find_all_match_items();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].bundle_id, "com.hashed");
+        assert!(rules[0].match_value.starts_with(URL_HASH_PREFIX));
+        assert!(storage
+            .find_rules_matching("https://github.com/pulls?token=1")
+            .iter()
+            .all(|item| !item.match_value.contains("token")));
+    }
+
+    #[test]
+    fn test_legacy_path_migration() {
+        let db_path = test_db_path("legacy_path_migration");
+        {
+            let connection = Connection::open(&db_path).unwrap();
+            connection.execute_batch("
+            CREATE TABLE browsers (path text primary key, name text not null, icon_data blob not null);
+            CREATE TABLE browser_profiles (browser_path text not null, profile text not null, description text, primary key(browser_path, profile));
+            CREATE TABLE matches (browser_path text not null, profile text, match_type text not null, match_value text primary key);
+            INSERT INTO browsers VALUES ('/Applications/Google Chrome.app', 'Google Chrome', x'00');
+            INSERT INTO browser_profiles VALUES ('/Applications/Google Chrome.app', 'Profile 1', NULL);
+            INSERT INTO browser_profiles VALUES ('/Applications/Gone.app', 'Default', NULL);
+            INSERT INTO matches VALUES ('/Applications/Google Chrome.app', 'Profile 1', 'Contain', 'github.com');
+            INSERT INTO matches VALUES ('/Applications/Gone.app', NULL, 'Equal', 'https://gone.example');
+            ").unwrap();
+        }
+
+        let storage = Storage::open(&db_path);
+        assert!(storage.get_browsers().is_empty());
+        storage.resolve_bundle_ids(|path| {
+            (path == "/Applications/Google Chrome.app").then(|| "com.google.Chrome".to_string())
+        });
+
+        let matched = storage
+            .find_contain_matches_by_url("https://github.com/rust-lang".to_string())
+            .unwrap();
+        assert_eq!(matched.bundle_id, "com.google.Chrome");
+        assert_eq!(matched.browser_path, "/Applications/Google Chrome.app");
+        assert_eq!(matched.profile, Some("Profile 1".to_string()));
+        assert!(!matched.private);
+        let profiles = storage.get_browser_profiles("com.google.Chrome".to_string());
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].profile, "Profile 1");
+
+        let unresolved = storage
+            .find_equal_matches_by_url("https://gone.example".to_string())
+            .unwrap();
+        assert_eq!(unresolved.bundle_id, "/Applications/Gone.app");
+        assert_eq!(
+            storage
+                .get_browser_profiles("/Applications/Gone.app".to_string())
+                .len(),
+            1
+        );
+    }
 }
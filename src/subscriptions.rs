@@ -1,5 +1,10 @@
-use iced::futures::Stream;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream, StreamExt};
 use iced::stream;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::warn;
 use tray_icon::menu::MenuEvent;
 
 use crate::message::{Message, WindowType};
@@ -20,3 +25,46 @@ pub fn tray_menu_event_subscription() -> impl Stream<Item = Message> {
         }
     })
 }
+
+pub fn app_dirs_subscription(dirs: Vec<PathBuf>) -> impl Stream<Item = Message> {
+    stream::channel(1, |mut output| async move {
+        let (sender, mut receiver) = mpsc::unbounded();
+        let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            let outside_bundles = event.paths.iter().any(|path| {
+                !path
+                    .ancestors()
+                    .skip(1)
+                    .any(|parent| parent.extension().and_then(|s| s.to_str()) == Some("app"))
+            });
+            if outside_bundles {
+                let _ = sender.unbounded_send(());
+            }
+        });
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                warn!("failed to watch application directories: {}", err);
+                return;
+            }
+        };
+        for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+            if let Err(err) = watcher.watch(dir, RecursiveMode::Recursive) {
+                warn!("failed to watch {}: {}", dir.display(), err);
+            }
+        }
+        while receiver.next().await.is_some() {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            while let Ok(Some(())) = receiver.try_next() {}
+            if output.send(Message::CheckInstalledApps).await.is_err() {
+                break;
+            }
+        }
+        drop(watcher);
+    })
+}